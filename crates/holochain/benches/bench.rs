@@ -84,6 +84,7 @@ pub fn wasm_call_n(c: &mut Criterion) {
                     expires_at: Timestamp::now(),
                     nonce: [0; 32].into(),
                     signature: [0; 64].into(),
+                    call_id: 0,
                 };
                 REAL_RIBOSOME
                     .lock()
@@ -5,6 +5,7 @@ use crate::conductor::api::error::SerializationError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
 use crate::conductor::ConductorHandle;
+use crate::core::ribosome::fresh_call_id;
 
 use holochain_serialized_bytes::prelude::*;
 
@@ -64,26 +65,38 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     .await?,
             )),
             AppRequest::CallZome(call) => {
-                match self.conductor_handle.call_zome(*call.clone()).await? {
-                    Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCalled(Box::new(output))),
-                    Ok(ZomeCallResponse::Unauthorized(zome_call_authorization, _, zome_name, fn_name, _)) => Ok(AppResponse::Error(
+                let call_id = fresh_call_id();
+                let error = match self
+                    .conductor_handle
+                    .call_zome_with_id(*call.clone(), call_id)
+                    .await
+                {
+                    Ok(Ok(ZomeCallResponse::Ok(output))) => {
+                        return Ok(AppResponse::ZomeCalled {
+                            call_id,
+                            response: Box::new(output),
+                        })
+                    }
+                    Ok(Ok(ZomeCallResponse::Unauthorized(zome_call_authorization, _, zome_name, fn_name, _))) => {
                         ExternalApiWireError::ZomeCallUnauthorized(format!(
                             "Call was not authorized with reason {:?}, cap secret {:?} to call the function {} in zome {}",
                             zome_call_authorization, call.cap_secret, fn_name, zome_name
-                        )),
-                    )),
-                    Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
+                        ))
+                    }
+                    Ok(Ok(ZomeCallResponse::NetworkError(e))) => unreachable!(
                         "Interface zome calls should never be routed to the network. This is a bug. Got {}",
                         e
                     ),
-                    Ok(ZomeCallResponse::CountersigningSession(e)) => Ok(AppResponse::Error(
+                    Ok(Ok(ZomeCallResponse::CountersigningSession(e))) => {
                         ExternalApiWireError::CountersigningSessionError(format!(
                             "A countersigning session has failed to start on this zome call because: {}",
                             e
-                        )),
-                    )),
-                    Err(e) => Ok(AppResponse::Error(e.into())),
-                }
+                        ))
+                    }
+                    Ok(Err(e)) => e.into(),
+                    Err(e) => e.into(),
+                };
+                Ok(AppResponse::ZomeCallFailed { call_id, error })
             }
             AppRequest::CreateCloneCell(payload) => {
                 let clone_cell = self
@@ -13,6 +13,7 @@ use crate::conductor::cell::error::CellResult;
 use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
+use crate::core::ribosome::fresh_call_id;
 use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::ZomeCallInvocation;
@@ -317,6 +318,7 @@ impl Cell {
                                     continue;
                                 }
                             },
+                            fresh_call_id(),
                             None,
                         ),
                     );
@@ -398,13 +400,14 @@ impl Cell {
                 payload,
                 nonce,
                 expires_at,
+                call_id,
                 ..
             } => {
                 async {
                     let res = self
                         .handle_call_remote(
                             from_agent, signature, zome_name, fn_name, cap_secret, payload, nonce,
-                            expires_at,
+                            expires_at, call_id,
                         )
                         .await
                         .map_err(holochain_p2p::HolochainP2pError::other);
@@ -706,6 +709,13 @@ impl Cell {
         let receipt: SignedValidationReceipt = receipt.try_into()?;
         tracing::debug!(from = ?receipt.receipt.validators, to = ?self.id.agent_pubkey(), hash = ?receipt.receipt.dht_op_hash);
 
+        // Receipts are stored whatever their validation status, as rejections
+        // are evidence too, but only if the validators really signed them.
+        if !receipt.verify().await {
+            tracing::warn!(from = ?receipt.receipt.validators, hash = ?receipt.receipt.dht_op_hash, "Dropping a validation receipt with invalid signatures");
+            return Ok(());
+        }
+
         // Get the action for this op so we can check the entry type.
         let hash = receipt.receipt.dht_op_hash.clone();
         let action: Option<SignedAction> = self
@@ -760,22 +770,20 @@ impl Cell {
         self.space
             .dht_db
             .async_commit(move |txn| {
-                // Get the current count for this dhtop.
-                let receipt_count: usize = txn.query_row(
-                    "SELECT COUNT(rowid) FROM ValidationReceipt WHERE op_hash = :op_hash",
-                    named_params! {
-                        ":op_hash": receipt.receipt.dht_op_hash,
-                    },
-                    |row| row.get(0),
-                )?;
+                let op_hash = receipt.receipt.dht_op_hash.clone();
+
+                // Add to receipts db
+                validation_receipts::add_if_unique(txn, receipt)?;
+
+                // Get the current count for this dhtop, including this receipt.
+                let receipt_count = validation_receipts::count_valid(txn, &op_hash)?;
 
                 // If we have enough receipts then set receipts to complete.
                 if receipt_count >= required_validation_count as usize {
-                    set_receipts_complete(txn, &receipt.receipt.dht_op_hash, true)?;
+                    set_receipts_complete(txn, &op_hash, true)?;
                 }
 
-                // Add to receipts db
-                validation_receipts::add_if_unique(txn, receipt)
+                StateMutationResult::Ok(())
             })
             .await?;
 
@@ -791,6 +799,7 @@ impl Cell {
     #[instrument(skip(self, from_agent, fn_name, cap_secret, payload))]
     #[allow(clippy::too_many_arguments)]
     /// a remote agent is attempting a "call_remote" on this cell.
+    /// The call runs under the caller's call id if it sent one.
     async fn handle_call_remote(
         &self,
         from_agent: AgentPubKey,
@@ -801,6 +810,7 @@ impl Cell {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: Option<u64>,
     ) -> CellResult<SerializedBytes> {
         let invocation = ZomeCall {
             cell_id: self.id.clone(),
@@ -816,7 +826,11 @@ impl Cell {
         // double ? because
         // - ConductorApiResult
         // - ZomeCallResult
-        Ok(self.call_zome(invocation, None).await??.try_into()?)
+        let call_id = call_id.unwrap_or_else(fresh_call_id);
+        Ok(self
+            .call_zome(invocation, call_id, None)
+            .await??
+            .try_into()?)
     }
 
     /// Function called by the Conductor
@@ -824,6 +838,7 @@ impl Cell {
     pub async fn call_zome(
         &self,
         call: ZomeCall,
+        call_id: u64,
         workspace_lock: Option<SourceChainWorkspace>,
     ) -> CellResult<ZomeCallResult> {
         // Only check if init has run if this call is not coming from
@@ -842,7 +857,8 @@ impl Cell {
         let signal_tx = self.signal_broadcaster();
         let ribosome = self.get_ribosome()?;
         let invocation =
-            ZomeCallInvocation::try_from_interface_call(self.conductor_api.clone(), call).await?;
+            ZomeCallInvocation::try_from_interface_call(self.conductor_api.clone(), call, call_id)
+                .await?;
 
         let dna_def = ribosome.dna_def().as_content().clone();
 
@@ -64,6 +64,7 @@ use crate::conductor::p2p_agent_store::list_all_agent_info;
 use crate::conductor::p2p_agent_store::query_peer_density;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueConsumerMap;
+use crate::core::ribosome::fresh_call_id;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CHANNEL_BOUND;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CONCURRENT_LIMIT;
@@ -304,9 +305,13 @@ mod startup_shutdown_impls {
             let ghost_shutdown = self.holochain_p2p.ghost_actor_shutdown_immediate();
             let mut tm = self.task_manager();
             let task = self.detach_task_management().expect("Attempting to shut down after already detaching task management or previous shutdown");
+            let spaces = self.spaces.clone();
             tokio::task::spawn(async move {
                 tracing::info!("Sending shutdown signal to all managed tasks.");
                 let (_, _, r) = futures::join!(ghost_shutdown, tm.shutdown().boxed(), task,);
+                // Release the database handles so a conductor started later
+                // in this process opens the databases afresh.
+                spaces.close();
                 r?
             })
         }
@@ -549,6 +554,9 @@ mod dna_impls {
         )> {
             let db = &self.spaces.wasm_db;
 
+            // Make any dna defs stored by an older version findable by name.
+            db.async_commit(holochain_state::dna_def::reindex).await?;
+
             // Load out all dna defs
             let (wasms, defs) = db
                 .async_reader(move |txn| {
@@ -628,10 +636,29 @@ mod dna_impls {
                     .filter_map(|cell_id| cells.remove(cell_id).map(|c| (cell_id, c)))
                     .collect()
             });
+            let mut dna_hashes = HashSet::new();
             for (cell_id, item) in to_cleanup {
                 if let Err(err) = item.cell.cleanup().await {
                     tracing::error!("Error cleaning up Cell: {:?}\nCellId: {}", err, cell_id);
                 }
+                dna_hashes.insert(cell_id.dna_hash().clone());
+            }
+            self.remove_unused_spaces(dna_hashes);
+        }
+
+        /// Remove the spaces, and close the databases, of any of these dnas
+        /// which no longer have a running cell.
+        pub(crate) fn remove_unused_spaces(&self, dna_hashes: impl IntoIterator<Item = DnaHash>) {
+            let running: HashSet<DnaHash> = self.running_cells.share_ref(|cells| {
+                cells
+                    .keys()
+                    .map(|cell_id| cell_id.dna_hash().clone())
+                    .collect()
+            });
+            for dna_hash in dna_hashes {
+                if !running.contains(&dna_hash) {
+                    self.spaces.remove_space(&dna_hash);
+                }
             }
         }
 
@@ -1015,8 +1042,18 @@ mod network_impls {
 
         /// Invoke a zome function on a Cell
         pub async fn call_zome(&self, call: ZomeCall) -> ConductorApiResult<ZomeCallResult> {
+            self.call_zome_with_id(call, fresh_call_id()).await
+        }
+
+        /// Invoke a zome function on a Cell under the given call id, which
+        /// its traces and any remote calls it makes will carry.
+        pub async fn call_zome_with_id(
+            &self,
+            call: ZomeCall,
+            call_id: u64,
+        ) -> ConductorApiResult<ZomeCallResult> {
             let cell = self.cell_by_id(&call.cell_id)?;
-            Ok(cell.call_zome(call, None).await?)
+            Ok(cell.call_zome(call, call_id, None).await?)
         }
 
         pub(crate) async fn call_zome_with_workspace(
@@ -1026,7 +1063,9 @@ mod network_impls {
         ) -> ConductorApiResult<ZomeCallResult> {
             debug!(cell_id = ?call.cell_id);
             let cell = self.cell_by_id(&call.cell_id)?;
-            Ok(cell.call_zome(call, Some(workspace_lock)).await?)
+            Ok(cell
+                .call_zome(call, fresh_call_id(), Some(workspace_lock))
+                .await?)
         }
     }
 }
@@ -2211,38 +2250,24 @@ impl Conductor {
 
         // For any unrepresented DNAs, clean up those DNA-specific databases
         for dna_hash in dnas_to_cleanup {
-            futures::future::join_all(
-                [
-                    self.spaces
-                        .authored_db(dna_hash)
-                        .unwrap()
-                        .async_commit(|txn| {
-                            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
-                        })
-                        .boxed(),
-                    self.spaces
-                        .dht_db(dna_hash)
-                        .unwrap()
-                        .async_commit(|txn| {
-                            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
-                        })
-                        .boxed(),
-                    self.spaces
-                        .cache(dna_hash)
-                        .unwrap()
-                        .async_commit(|txn| {
-                            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
-                        })
-                        .boxed(),
-                    // TODO: also delete stale Wasms
-                ]
-                .into_iter(),
-            )
-            .await
-            .into_iter()
-            .collect::<Result<Vec<usize>, _>>()?;
+            let authored_db = self.spaces.authored_db(dna_hash)?;
+            let dht_db = self.spaces.dht_db(dna_hash)?;
+            let cache = self.spaces.cache(dna_hash)?;
+            futures::future::try_join_all([
+                authored_db.clear_all().boxed(),
+                dht_db.clear_all().boxed(),
+                cache.clear_all().boxed(),
+                // TODO: also delete stale Wasms
+            ])
+            .await?;
         }
 
+        self.remove_unused_spaces(
+            cells_to_cleanup
+                .iter()
+                .map(|cell| cell.id().dna_hash().clone()),
+        );
+
         Ok(())
     }
 
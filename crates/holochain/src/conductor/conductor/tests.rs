@@ -394,123 +394,62 @@ async fn test_signing_error_during_genesis() {
     }
 }
 
-// async fn make_signing_call(
-//     conductor: &SweetConductor,
-//     client: &mut WebsocketSender,
-//     keystore_control: &MockLairControl,
-//     cell: &SweetCell,
-// ) -> AppResponse {
-//     let reinstate_mock = keystore_control.using_mock();
-//     if reinstate_mock {
-//         keystore_control.use_real();
-//     }
-//     let (nonce, expires_at) = fresh_nonce(Timestamp::now()).unwrap();
-//     let request = AppRequest::CallZome(Box::new(
-//         ZomeCall::try_from_unsigned_zome_call(
-//             conductor.raw_handle().keystore(),
-//             ZomeCallUnsigned {
-//                 cell_id: cell.cell_id().clone(),
-//                 zome_name: "sign".into(),
-//                 fn_name: "sign_ephemeral".into(),
-//                 payload: ExternIO::encode(()).unwrap(),
-//                 cap_secret: None,
-//                 provenance: cell.agent_pubkey().clone(),
-//                 nonce,
-//                 expires_at,
-//             },
-//         )
-//         .await
-//         .unwrap(),
-//     ));
-//     if reinstate_mock {
-//         keystore_control.use_mock();
-//     }
-//     client.request(request).await.unwrap()
-// }
-
-// A test which simulates Keystore errors with a test keystore which is designed
-// to fail.
-//
-// This test was written making the assumption that we could swap out the
-// MetaLairClient for each Cell at runtime, but given our current concurrency
-// model which puts each Cell in an Arc, this is not possible.
-// In order to implement this test, we should probably have the "crude mock
-// keystore" listen on a channel which toggles its behavior from always-correct
-// to always-failing. However, the problem that this test is testing for does
-// not seem to be an issue, therefore I'm not putting the effort into fixing it
-// right now.
-// @todo fix test by using new InstallApp call
-// #[tokio::test(flavor = "multi_thread")]
-// async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
-//     observability::test_run().ok();
-//     let (keystore, keystore_control) = spawn_real_or_mock_keystore(|_| Err("test error".into()))
-//         .await
-//         .unwrap();
-
-//     let db_dir = test_db_dir();
-//     let config = standard_config();
-//     let mut conductor = SweetConductor::new(
-//         SweetConductor::handle_from_existing(db_dir.path(), keystore.clone(), &config, &[]).await,
-//         db_dir.into(),
-//         config,
-//     )
-//     .await;
-
-//     let (agent1, agent2, agent3) = SweetAgents::three(keystore.clone()).await;
-
-//     let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Sign]).await;
-
-//     let app1 = conductor
-//         .setup_app_for_agent("app1", agent1.clone(), &[dna.clone()])
-//         .await
-//         .unwrap();
-
-//     let app2 = conductor
-//         .setup_app_for_agent("app2", agent2.clone(), &[dna.clone()])
-//         .await
-//         .unwrap();
-
-//     let (cell1,) = app1.into_tuple();
-//     let (cell2,) = app2.into_tuple();
-
-//     let app_port = conductor
-//         .raw_handle()
-//         .add_app_interface(either::Either::Left(0))
-//         .await
-//         .unwrap();
-//     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
-//     let (mut admin_client, _) = conductor.admin_ws_client().await;
-
-//     // Now use the bad keystore to cause a signing error on the next zome call
-//     keystore_control.use_mock();
-
-//     let response: AdminResponse = admin_client
-//         .request(AdminRequest::InstallApp(Box::new(InstallAppPayload {
-//             installed_app_id: "app3".into(),
-//             agent_key: agent3.clone(),
-//             dnas: vec![InstallAppDnaPayload {
-//                 role_name: "whatever".into(),
-//                 hash: dna.dna_hash().clone(),
-//                 membrane_proof: None,
-//             }],
-//         })))
-//         .await
-//         .unwrap();
-
-// assert_matches!(response, AdminResponse::Error(_));
-// let response = make_signing_call(&conductor, &mut app_client, &keystore_control, &cell2).await;
-
-//     assert_matches!(response, AppResponse::Error(_));
-
-//     // Go back to the good keystore, see if we can proceed
-//     keystore_control.use_real();
-
-// let response = make_signing_call(&conductor, &mut app_client, &keystore_control, &cell2).await;
-// assert_matches!(response, AppResponse::ZomeCall(_));
-
-// let response = make_signing_call(&conductor, &mut app_client, &keystore_control, &cell1).await;
-// assert_matches!(response, AppResponse::ZomeCall(_));
-// }
+#[tokio::test(flavor = "multi_thread")]
+async fn test_signing_error_during_zome_call_is_recoverable() {
+    observability::test_run().ok();
+    let mock = holochain_state::test_utils::test_keystore_deterministic([0; 32]);
+
+    let db_dir = test_db_dir();
+    let config = ConductorConfig::default();
+    let mut conductor = SweetConductor::new(
+        SweetConductor::handle_from_existing(db_dir.path(), mock.keystore(), &config, &[]).await,
+        db_dir.into(),
+        config,
+    )
+    .await;
+
+    let (dna, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(("zome", simple_create_entry_zome())).await;
+    let agent = mock.agent(0).await.unwrap();
+    let app = conductor
+        .setup_app_for_agent("app", agent, &[dna])
+        .await
+        .unwrap();
+    let (cell,) = app.into_tuple();
+    let zome = cell.zome("zome");
+
+    let first: ActionHash = conductor.call(&zome, "create", ()).await;
+
+    // - Let the zome call itself be signed, but fail signing the action it
+    //   creates.
+    let signed = Arc::new(AtomicU32::new(0));
+    mock.fail_with({
+        let signed = signed.clone();
+        move |request| match request {
+            kitsune_p2p_types::dependencies::lair_keystore_api::prelude::LairApiEnum::ReqSignByPubKey(_)
+                if signed.fetch_add(1, Ordering::SeqCst) > 0 =>
+            {
+                Some("test error".into())
+            }
+            _ => None,
+        }
+    });
+    let result: ConductorApiResult<ActionHash> = conductor.call_fallible(&zome, "create", ()).await;
+    let err = result.expect_err("signing the action should have failed");
+    assert!(err.to_string().contains("test error"), "{:?}", err);
+    assert_eq!(signed.load(Ordering::SeqCst), 2);
+
+    // - Once the keystore is back, the cell carries on from where its chain
+    //   left off, with nothing written by the failed call.
+    mock.clear_failure();
+    let second: ActionHash = conductor.call(&zome, "create", ()).await;
+    let mut seqs = Vec::new();
+    for hash in [first, second] {
+        let record: Option<Record> = conductor.call(&zome, "get", hash).await;
+        seqs.push(record.unwrap().action().action_seq());
+    }
+    assert_eq!(seqs[1], seqs[0] + 1);
+}
 
 pub(crate) fn simple_create_entry_zome() -> InlineIntegrityZome {
     let unit_entry_def = EntryDef::from_id("unit");
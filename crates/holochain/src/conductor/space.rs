@@ -4,7 +4,7 @@
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use holo_hash::{AgentPubKey, DhtOpHash, DnaHash};
-use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath};
+use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath, DbQuotaConfig};
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::{
     dht::{
@@ -23,7 +23,7 @@ use holochain_sqlite::{
     conn::{DbSyncLevel, DbSyncStrategy},
     db::{
         DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents, DbKindP2pMetrics,
-        DbKindWasm, DbWrite, ReadAccess,
+        DbKindWasm, DbName, DbWrite, ReadAccess,
     },
     prelude::{DatabaseError, DatabaseResult},
 };
@@ -74,6 +74,7 @@ pub struct Spaces {
     map: RwShare<HashMap<DnaHash, Space>>,
     pub(crate) db_dir: Arc<DatabaseRootPath>,
     pub(crate) db_sync_strategy: DbSyncStrategy,
+    pub(crate) db_quota: Option<DbQuotaConfig>,
     /// The map of running queue consumer workflows.
     pub(crate) queue_consumer_map: QueueConsumerMap,
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
@@ -156,6 +157,7 @@ impl Spaces {
             map: RwShare::new(HashMap::new()),
             db_dir: Arc::new(root_db_dir),
             db_sync_strategy,
+            db_quota: config.db_quota.clone(),
             queue_consumer_map: QueueConsumerMap::new(),
             conductor_db,
             wasm_db,
@@ -252,6 +254,7 @@ impl Spaces {
                             Arc::new(dna_hash.clone()),
                             &self.db_dir,
                             self.db_sync_strategy,
+                            self.db_quota.as_ref(),
                         )?;
 
                         let r = f(&space);
@@ -262,6 +265,30 @@ impl Spaces {
         }
     }
 
+    /// Remove a space and close its databases so the next
+    /// [`Spaces::get_or_create_space`] for this dna opens them afresh.
+    /// Returns true if the space existed.
+    pub fn remove_space(&self, dna_hash: &DnaHash) -> bool {
+        match self.map.share_mut(|spaces| spaces.remove(dna_hash)) {
+            Some(space) => {
+                space.close();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every space and close all databases, including the
+    /// conductor and wasm databases.
+    pub fn close(&self) {
+        let spaces = self.map.share_mut(std::mem::take);
+        for space in spaces.into_values() {
+            space.close();
+        }
+        self.conductor_db.clone().close();
+        self.wasm_db.clone().close();
+    }
+
     /// Get the cache database (this will create the space if it doesn't already exist).
     pub fn cache(&self, dna_hash: &DnaHash) -> ConductorResult<DbWrite<DbKindCache>> {
         self.get_or_create_space_ref(dna_hash, |space| space.cache_db.clone())
@@ -648,6 +675,7 @@ impl Space {
         dna_hash: Arc<DnaHash>,
         root_db_dir: &DatabaseRootPath,
         db_sync_strategy: DbSyncStrategy,
+        db_quota: Option<&DbQuotaConfig>,
     ) -> ConductorResult<Self> {
         use holochain_p2p::DnaHashExt;
         let space = dna_hash.to_kitsune();
@@ -683,6 +711,16 @@ impl Space {
         let conductor_db: DbWrite<DbKindConductor> =
             DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindConductor, db_sync_level)?;
 
+        // The conductor database is shared by every space so it has no quota.
+        if let Some(db_quota) = db_quota {
+            cache.set_quota(db_quota.quota_for(DbName::Cache));
+            authored_db.set_quota(db_quota.quota_for(DbName::Authored));
+            dht_db.set_quota(db_quota.quota_for(DbName::Dht));
+            p2p_agents_db.set_quota(db_quota.quota_for(DbName::P2pAgentStore));
+            p2p_metrics_db.set_quota(db_quota.quota_for(DbName::P2pMetrics));
+        }
+        cache.set_quota_eviction(holochain_state::cache::evict_for_quota);
+
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         tokio::spawn(p2p_agent_store::p2p_put_all_batch(
             p2p_agents_db.clone(),
@@ -711,6 +749,16 @@ impl Space {
         Ok(r)
     }
 
+    /// Close the databases owned by this space.
+    /// The conductor database is shared by all spaces so it is left open.
+    fn close(self) {
+        self.cache_db.close();
+        self.authored_db.close();
+        self.dht_db.close();
+        self.p2p_agents_db.close();
+        self.p2p_metrics_db.close();
+    }
+
     /// Construct a SourceChain for an author in this Space
     pub async fn source_chain(
         &self,
@@ -780,6 +828,7 @@ impl TestSpace {
                 Arc::new(dna_hash),
                 &temp_dir.path().to_path_buf().into(),
                 Default::default(),
+                None,
             )
             .unwrap(),
             _temp_dir: temp_dir,
@@ -140,3 +140,120 @@ async fn test_region_queries() {
     assert_eq!(fetched_ops.len(), NUM_OPS);
     assert_eq!(inserted_ops, fetched_ops);
 }
+
+/// Removing a space, or closing all spaces on shutdown, must release the
+/// cached database handles so the files can be deleted and recreated.
+#[tokio::test(flavor = "multi_thread")]
+async fn closed_space_databases_can_be_deleted_and_reopened() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config = ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        ..Default::default()
+    };
+    let spaces = Spaces::new(&config).unwrap();
+    let dna_hash = DnaHash::from_raw_32(vec![0; 32]);
+
+    let dht_path = spaces.dht_db(&dna_hash).unwrap().path().clone();
+    assert!(spaces.remove_space(&dna_hash));
+    assert!(!spaces.remove_space(&dna_hash));
+    std::fs::remove_file(&dht_path).unwrap();
+
+    // A stale handle would be handed back here without recreating the file.
+    spaces.dht_db(&dna_hash).unwrap();
+    assert!(dht_path.exists());
+
+    let conductor_path = spaces.conductor_db.path().clone();
+    spaces.close();
+    std::fs::remove_file(&dht_path).unwrap();
+    std::fs::remove_file(&conductor_path).unwrap();
+
+    let spaces = Spaces::new(&config).unwrap();
+    spaces.dht_db(&dna_hash).unwrap();
+    assert!(conductor_path.exists());
+    assert!(dht_path.exists());
+}
+
+/// A cache evicts its least recently used data to stay within the quota, the authored
+/// database is exempt and any other database refuses to grow past it.
+#[tokio::test(flavor = "multi_thread")]
+async fn db_quota_evicts_from_caches_and_fails_other_writes() {
+    use ::fixt::prelude::*;
+    use holochain_conductor_api::conductor::DbQuotaConfig;
+    use holochain_sqlite::error::DatabaseError;
+    use holochain_state::mutations::{insert_op, set_when_integrated, StateMutationError};
+
+    const LIMIT: u64 = 1_000_000;
+    const NUM_OPS: u8 = 20;
+
+    async fn insert_big_op<Kind: DbKindT>(
+        db: &DbWrite<Kind>,
+        i: u8,
+    ) -> Result<(), StateMutationError> {
+        let entry = Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(
+            vec![i; 100_000],
+        ))));
+        let mut create = fixt!(Create);
+        create.entry_hash = EntryHash::with_data_sync(&entry);
+        let op = DhtOpHashed::from_content_sync(DhtOp::StoreRecord(
+            fixt!(Signature),
+            Action::Create(create),
+            Some(Box::new(entry)),
+        ));
+        db.async_commit(move |txn| {
+            insert_op(txn, &op)?;
+            set_when_integrated(txn, op.as_hash(), Timestamp::now())
+        })
+        .await
+    }
+    let count_entries = |db: &DbWrite<DbKindCache>| {
+        db.conn().unwrap().with_reader_test(|txn| {
+            txn.query_row("SELECT COUNT(*) FROM Entry", [], |row| {
+                row.get::<_, usize>(0)
+            })
+            .unwrap()
+        })
+    };
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        db_quota: Some(DbQuotaConfig {
+            limit_bytes: LIMIT,
+            mode: QuotaMode::HardFail,
+            exempt: vec![DbName::Authored],
+        }),
+        ..Default::default()
+    })
+    .unwrap();
+    let dna_hash = DnaHash::from_raw_32(vec![0; 32]);
+
+    let cache = spaces.cache(&dna_hash).unwrap();
+    for i in 0..NUM_OPS {
+        insert_big_op(&cache, i).await.unwrap();
+    }
+    assert!(cache.stats().await.unwrap().used_bytes() <= LIMIT);
+    let remaining = count_entries(&cache);
+    assert!(remaining > 0 && remaining < NUM_OPS as usize);
+
+    let authored = spaces.authored_db(&dna_hash).unwrap();
+    for i in 0..NUM_OPS {
+        insert_big_op(&authored, i).await.unwrap();
+    }
+    assert!(authored.stats().await.unwrap().used_bytes() > LIMIT);
+
+    let dht = spaces.dht_db(&dna_hash).unwrap();
+    let mut result = Ok(());
+    for i in 0..NUM_OPS {
+        result = insert_big_op(&dht, i).await;
+        if result.is_err() {
+            break;
+        }
+    }
+    assert!(matches!(
+        result,
+        Err(StateMutationError::DatabaseError(
+            DatabaseError::QuotaExceeded { limit: LIMIT, .. }
+        ))
+    ));
+    assert!(dht.stats().await.unwrap().used_bytes() <= LIMIT);
+}
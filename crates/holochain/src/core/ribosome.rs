@@ -54,12 +54,20 @@ use self::{
     guest_callback::genesis_self_check::{GenesisSelfCheckHostAccess, GenesisSelfCheckInvocation},
 };
 
+/// A new id to tell a call into the ribosome apart from others in the logs.
+/// Ids are random rather than counted so that the ids of remote calls, which
+/// are chosen by the calling node, don't collide with those chosen locally.
+pub fn fresh_call_id() -> u64 {
+    rand::random()
+}
+
 #[derive(Clone)]
 pub struct CallContext {
     pub(crate) zome: Zome,
     pub(crate) function_name: FunctionName,
     pub(crate) auth: InvocationAuth,
     pub(crate) host_context: HostContext,
+    pub(crate) call_id: u64,
 }
 
 impl CallContext {
@@ -74,6 +82,7 @@ impl CallContext {
             function_name,
             host_context,
             auth,
+            call_id: fresh_call_id(),
         }
     }
 
@@ -92,6 +101,14 @@ impl CallContext {
     pub fn auth(&self) -> InvocationAuth {
         self.auth.clone()
     }
+
+    /// The id of this call, recorded on the wasm traces the call emits so
+    /// interleaved calls can be told apart. A zome call runs under the id of
+    /// its [`ZomeCallInvocation`], which is returned to the client and sent
+    /// along with any remote calls it makes.
+    pub fn call_id(&self) -> u64 {
+        self.call_id
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -304,6 +321,10 @@ pub trait Invocation: Clone {
     /// careful about cloning invocations
     fn host_input(self) -> Result<ExternIO, SerializedBytesError>;
     fn auth(&self) -> InvocationAuth;
+    /// The id the call must run under, or `None` for the ribosome to pick one.
+    fn call_id(&self) -> Option<u64> {
+        None
+    }
 }
 
 impl ZomeCallInvocation {
@@ -432,6 +453,8 @@ pub struct ZomeCallInvocation {
     pub nonce: Nonce256Bits,
     /// This call MUST NOT be respected after this time, in the opinion of the callee.
     pub expires_at: Timestamp,
+    /// The id of the call, see [`CallContext::call_id`].
+    pub call_id: u64,
 }
 
 impl Invocation for ZomeCallInvocation {
@@ -447,12 +470,16 @@ impl Invocation for ZomeCallInvocation {
     fn auth(&self) -> InvocationAuth {
         InvocationAuth::Cap(self.provenance.clone(), self.cap_secret)
     }
+    fn call_id(&self) -> Option<u64> {
+        Some(self.call_id)
+    }
 }
 
 impl ZomeCallInvocation {
     pub async fn try_from_interface_call(
         conductor_api: CellConductorHandle,
         call: ZomeCall,
+        call_id: u64,
     ) -> RibosomeResult<Self> {
         let ZomeCall {
             cell_id,
@@ -478,6 +505,7 @@ impl ZomeCallInvocation {
             signature,
             nonce,
             expires_at,
+            call_id,
         })
     }
 }
@@ -494,6 +522,7 @@ impl From<ZomeCallInvocation> for ZomeCall {
             signature,
             nonce,
             expires_at,
+            ..
         } = inv;
         Self {
             cell_id,
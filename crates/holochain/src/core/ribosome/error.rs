@@ -31,9 +31,19 @@ pub enum RibosomeError {
     #[error("Referenced a zome that doesn't exist: Zome: {0}")]
     ZomeNotExists(ZomeName),
 
-    /// A ZomeFn was called by name that doesn't exist
-    #[error("Attempted to call a zome function that doesn't exist: Zome: {0} Fn {1}")]
-    ZomeFnNotExists(ZomeName, FunctionName),
+    /// A ZomeFn was called by name that doesn't exist.
+    #[error(
+        "Attempted to call a zome function that doesn't exist: Zome: {zome} Fn {function}{}",
+        did_you_mean(.available)
+    )]
+    ZomeFnNotExists {
+        /// The zome that was called.
+        zome: ZomeName,
+        /// The function that doesn't exist.
+        function: FunctionName,
+        /// The zome's functions with names similar to `function`.
+        available: Vec<FunctionName>,
+    },
 
     /// a problem with entry defs
     #[error("An error with entry defs in zome '{0}': {1}")]
@@ -92,9 +102,28 @@ pub enum RibosomeError {
     #[error("Host function {2} cannot be called from zome function {1} in zome {0}")]
     HostFnPermissions(ZomeName, FunctionName, String),
 
+    /// More random bytes were requested than a single call may return.
+    #[error("Requested {0} random bytes but at most {1} can be generated in one call")]
+    RandomBytesTooMany(u32, u32),
+
     #[error(transparent)]
     ZomeTypesError(#[from] holochain_types::zome_types::ZomeTypesError),
 }
 
+fn did_you_mean(suggestions: &[FunctionName]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(
+            ". Did you mean: {}?",
+            suggestions
+                .iter()
+                .map(|f| f.0.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
 /// Type alias
 pub type RibosomeResult<T> = Result<T, RibosomeError>;
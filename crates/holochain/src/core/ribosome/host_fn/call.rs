@@ -92,6 +92,7 @@ pub fn call(
                                         zome_call_unsigned.payload,
                                         zome_call_unsigned.nonce,
                                         zome_call_unsigned.expires_at,
+                                        call_context.call_id(),
                                     )
                                     .await
                                 {
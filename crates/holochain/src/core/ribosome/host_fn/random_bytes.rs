@@ -6,6 +6,10 @@ use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
 use std::sync::Arc;
 
+/// The most random bytes a single call may ask for, so that a zome can't
+/// make the host allocate an arbitrary amount of memory.
+pub const MAX_RANDOM_BYTES: u32 = 1024 * 1024;
+
 /// return n crypto secure random bytes from the standard holochain crypto lib
 pub fn random_bytes(
     _ribosome: Arc<impl RibosomeT>,
@@ -17,6 +21,12 @@ pub fn random_bytes(
             non_determinism: Permission::Allow,
             ..
         } => {
+            if input > MAX_RANDOM_BYTES {
+                return Err(wasm_error!(WasmErrorInner::Host(
+                    RibosomeError::RandomBytesTooMany(input, MAX_RANDOM_BYTES).to_string()
+                ))
+                .into());
+            }
             let mut bytes = vec![0; input as _];
             getrandom::getrandom(&mut bytes)
                 .map_err(|error| -> RuntimeError {
@@ -41,6 +51,8 @@ pub fn random_bytes(
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
     use crate::core::ribosome::host_fn::random_bytes::random_bytes;
+    use crate::core::ribosome::host_fn::random_bytes::MAX_RANDOM_BYTES;
+    use crate::core::ribosome::RibosomeError;
 
     use crate::core::ribosome::wasm_test::RibosomeTestFixture;
     use crate::core::ribosome::HostContext;
@@ -71,6 +83,39 @@ pub mod wasm_test {
         assert_ne!(&[0; LEN as usize], output.as_ref(),);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    /// requests are limited in size but may be empty
+    async fn random_bytes_size_limit_test() {
+        let ribosome = Arc::new(
+            RealRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+                .next()
+                .unwrap(),
+        );
+        let mut call_context = CallContextFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        call_context.host_context = HostContext::ZomeCall(fixt!(ZomeCallHostAccess));
+        let call_context = Arc::new(call_context);
+
+        let empty = random_bytes(ribosome.clone(), call_context.clone(), 0).unwrap();
+        assert!(empty.is_empty());
+
+        let most = random_bytes(ribosome.clone(), call_context.clone(), MAX_RANDOM_BYTES).unwrap();
+        assert_eq!(most.len(), MAX_RANDOM_BYTES as usize);
+
+        let err = random_bytes(ribosome.clone(), call_context.clone(), MAX_RANDOM_BYTES + 1)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&RibosomeError::RandomBytesTooMany(MAX_RANDOM_BYTES + 1, MAX_RANDOM_BYTES).to_string()));
+
+        // Two calls don't return the same bytes.
+        assert_ne!(
+            random_bytes(ribosome.clone(), call_context.clone(), 32).unwrap(),
+            random_bytes(ribosome, call_context, 32).unwrap(),
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     /// we can get some random data out of the fn via. a wasm call
     async fn ribosome_random_bytes_test() {
@@ -13,12 +13,29 @@ use std::sync::atomic::AtomicBool;
 
 #[cfg(test)]
 static CAPTURE: AtomicBool = AtomicBool::new(false);
+/// The traces captured in tests, with the id of the call that emitted them.
 #[cfg(test)]
-static CAPTURED: SyncLazy<Arc<std::sync::Mutex<Vec<TraceMsg>>>> =
+type CapturedTraces = Vec<(u64, TraceMsg)>;
+#[cfg(test)]
+static CAPTURED: SyncLazy<Arc<std::sync::Mutex<CapturedTraces>>> =
     SyncLazy::new(|| Arc::new(std::sync::Mutex::new(Vec::new())));
 
-#[instrument(skip(input))]
-pub fn wasm_trace(input: TraceMsg) {
+/// Forward a wasm trace to `tracing` inside a `wasm_trace` span that records
+/// where it came from, so `WASM_LOG` can filter on it, e.g.
+/// `[wasm_trace{zome=forum}]=debug`.
+/// The span's `call` field is the [`CallContext::call_id`] of the call that
+/// emitted the trace.
+#[instrument(
+    skip_all,
+    fields(dna = %dna_hash, zome = %zome_name, function = %function_name, call = call_id)
+)]
+pub fn wasm_trace(
+    input: TraceMsg,
+    dna_hash: &DnaHash,
+    zome_name: &ZomeName,
+    function_name: &FunctionName,
+    call_id: u64,
+) {
     match input.level {
         holochain_types::prelude::Level::TRACE => tracing::trace!("{}", input.msg),
         holochain_types::prelude::Level::DEBUG => tracing::debug!("{}", input.msg),
@@ -29,8 +46,8 @@ pub fn wasm_trace(input: TraceMsg) {
 }
 
 pub fn trace(
-    _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
     input: TraceMsg,
 ) -> Result<(), RuntimeError> {
     // Avoid dialing out to the environment on every trace.
@@ -44,10 +61,21 @@ pub fn trace(
 
     #[cfg(test)]
     if CAPTURE.load(std::sync::atomic::Ordering::Relaxed) {
-        CAPTURED.lock().unwrap().push(input.clone());
+        CAPTURED
+            .lock()
+            .unwrap()
+            .push((call_context.call_id(), input.clone()));
     }
 
-    tracing::subscriber::with_default(collector, || wasm_trace(input));
+    tracing::subscriber::with_default(collector, || {
+        wasm_trace(
+            input,
+            ribosome.dna_def().as_hash(),
+            call_context.zome.zome_name(),
+            call_context.function_name(),
+            call_context.call_id(),
+        )
+    });
     Ok(())
 }
 
@@ -62,6 +90,20 @@ pub mod wasm_test {
     use holochain_wasm_test_utils::TestWasm;
     use std::sync::Arc;
 
+    /// Collects formatted log output so tests can inspect it.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
     /// we can get an entry hash out of the fn directly
     #[tokio::test(flavor = "multi_thread")]
     async fn trace_test() {
@@ -81,6 +123,183 @@ pub mod wasm_test {
         assert_eq!((), output);
     }
 
+    #[test]
+    fn wasm_trace_can_be_filtered_by_zome() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let collector = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::new(
+                "[wasm_trace{zome=forum}]=debug",
+            ))
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+
+        let dna_hash = ::fixt::fixt!(DnaHash);
+        let msg = |msg: &str| TraceMsg {
+            level: holochain_types::prelude::Level::DEBUG,
+            msg: msg.into(),
+        };
+        tracing::subscriber::with_default(collector, || {
+            wasm_trace(msg("from forum"), &dna_hash, &"forum".into(), &"post".into(), 0);
+            wasm_trace(msg("from chat"), &dna_hash, &"chat".into(), &"post".into(), 1);
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("from forum"));
+        assert!(output.contains("function=post"));
+        assert!(output.contains(&format!("dna={}", dna_hash)));
+        assert!(!output.contains("from chat"));
+    }
+
+    #[test]
+    fn wasm_traces_record_their_call_id() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let collector = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::new("[wasm_trace]=debug"))
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+
+        let dna_hash = ::fixt::fixt!(DnaHash);
+        let first = CallContextFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        let mut second = first.clone();
+        second.call_id = crate::core::ribosome::fresh_call_id();
+        assert_ne!(first.call_id(), second.call_id());
+
+        let msg = |msg: &str| TraceMsg {
+            level: holochain_types::prelude::Level::DEBUG,
+            msg: msg.into(),
+        };
+        // Interleave the traces of the two calls.
+        tracing::subscriber::with_default(collector, || {
+            for (context, text) in [
+                (&first, "first a"),
+                (&second, "second a"),
+                (&first, "first b"),
+                (&second, "second b"),
+            ] {
+                wasm_trace(
+                    msg(text),
+                    &dna_hash,
+                    context.zome.zome_name(),
+                    context.function_name(),
+                    context.call_id(),
+                );
+            }
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let call_of = |text: &str| {
+            let line = output.lines().find(|line| line.contains(text)).unwrap();
+            line.split("call=")
+                .nth(1)
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        };
+        assert_eq!(call_of("first a"), first.call_id());
+        assert_eq!(call_of("first b"), first.call_id());
+        assert_eq!(call_of("second a"), second.call_id());
+        assert_eq!(call_of("second b"), second.call_id());
+    }
+
+    /// The client gets back the id a zome call ran under, and a remote call
+    /// made by it runs under the same id on the callee's node, so the traces
+    /// of both nodes can be matched up.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remote_calls_run_under_the_callers_call_id() {
+        use crate::conductor::api::AppInterfaceApi;
+        use crate::conductor::api::RealAppInterfaceApi;
+        use crate::sweettest::*;
+        use crate::test_utils::new_zome_call;
+        use holochain_conductor_api::AppRequest;
+        use holochain_conductor_api::AppResponse;
+        use std::collections::BTreeSet;
+
+        CAPTURE.store(true, std::sync::atomic::Ordering::SeqCst);
+        let trace = |msg: &str| TraceMsg {
+            level: holochain_types::prelude::Level::DEBUG,
+            msg: msg.into(),
+        };
+        let zome = InlineIntegrityZome::new_unique(Vec::new(), 0)
+            .function("grant", |api, ()| {
+                let mut functions = BTreeSet::new();
+                functions.insert((api.zome_info(()).unwrap().name, "pong".into()));
+                let hash = api.create(CreateInput::new(
+                    EntryDefLocation::CapGrant,
+                    EntryVisibility::Private,
+                    Entry::CapGrant(CapGrantEntry {
+                        tag: "".into(),
+                        access: ().into(),
+                        functions: GrantedFunctions::Listed(functions),
+                    }),
+                    ChainTopOrdering::default(),
+                ))?;
+                Ok(hash)
+            })
+            .function("ping", move |api, agent: AgentPubKey| {
+                api.trace(trace("call id ping"))?;
+                let response = api
+                    .call(vec![Call::new(
+                        CallTarget::NetworkAgent(agent),
+                        api.zome_info(()).unwrap().name,
+                        "pong".into(),
+                        None,
+                        ExternIO::encode(()).unwrap(),
+                    )])?
+                    .pop()
+                    .unwrap();
+                Ok(response)
+            })
+            .function("pong", move |api, ()| {
+                api.trace(trace("call id pong"))?;
+                Ok(())
+            });
+
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(("zome", zome)).await;
+        let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+        conductors.exchange_peer_info().await;
+        let ((alice,), (bob,)) = apps.into_tuples();
+        let _: ActionHash = conductors[1].call(&bob.zome("zome"), "grant", ()).await;
+
+        let call = new_zome_call(
+            &conductors[0].keystore(),
+            alice.cell_id(),
+            "ping",
+            bob.agent_pubkey().clone(),
+            "zome",
+        )
+        .await
+        .unwrap();
+        let response = RealAppInterfaceApi::new(conductors[0].raw_handle())
+            .handle_app_request(AppRequest::CallZome(Box::new(call)))
+            .await;
+        let (call_id, response) = match response {
+            AppResponse::ZomeCalled { call_id, response } => (call_id, response),
+            response => panic!("ping failed: {:?}", response),
+        };
+        let response: ZomeCallResponse = response.decode().unwrap();
+        matches::assert_matches!(response, ZomeCallResponse::Ok(_));
+
+        let call_of = |text: &str| {
+            CAPTURED
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(_, msg)| msg.msg == text)
+                .map(|(call_id, _)| *call_id)
+                .unwrap()
+        };
+        assert_eq!(call_of("call id ping"), call_id);
+        assert_eq!(call_of("call id pong"), call_id);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[ignore = "Doesn't work concurrently"]
     async fn wasm_trace_test() {
@@ -92,7 +311,12 @@ pub mod wasm_test {
         } = RibosomeTestFixture::new(TestWasm::Debug).await;
 
         let _: () = conductor.call(&alice, "debug", ()).await;
-        let r: Vec<_> = CAPTURED.lock().unwrap().clone();
+        let r: Vec<_> = CAPTURED
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, msg)| msg.clone())
+            .collect();
         let expect = vec![
             // two traces from the two validations of genesis entries
             TraceMsg {
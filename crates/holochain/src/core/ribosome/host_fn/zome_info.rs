@@ -37,8 +37,95 @@ pub fn zome_info(
 #[cfg(feature = "slow_tests")]
 pub mod test {
     use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use crate::core::ribosome::HostContext;
+    use crate::core::ribosome::RibosomeT;
+    use crate::fixt::curve;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::RealRibosomeFixturator;
+    use crate::fixt::ValidateHostAccessFixturator;
+    use ::fixt::prelude::*;
     use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::prelude::*;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn zome_info_reports_each_zomes_own_name() {
+        observability::test_run().ok();
+        let names: Vec<ZomeName> = vec!["first".into(), "second".into()];
+        let ribosome = Arc::new(
+            RealRibosomeFixturator::new(curve::ZomesNamed(
+                names
+                    .iter()
+                    .map(|name| (name.clone(), TestWasm::ZomeInfo))
+                    .collect(),
+            ))
+            .next()
+            .unwrap(),
+        );
+
+        for name in names {
+            let zome = ribosome.dna_def().get_zome(&name).unwrap();
+            let mut call_context = CallContextFixturator::new(curve::ForZome(zome))
+                .next()
+                .unwrap();
+            call_context.host_context = HostContext::Validate(
+                ValidateHostAccessFixturator::new(Unpredictable)
+                    .next()
+                    .unwrap(),
+            );
+            let zome_info =
+                super::zome_info(ribosome.clone(), Arc::new(call_context), ()).unwrap();
+            assert_eq!(zome_info.name, name);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn zome_info_reports_zome_names_of_a_given_dna() {
+        use holochain_types::prelude::{DnaFile, DnaWasm, DnaWasmHashed};
+        use holo_hash::HasHash;
+        use holochain_wasmer_host::prelude::wat2wasm;
+
+        let wasm = DnaWasmHashed::from_content(DnaWasm::from(
+            wat2wasm(br#"(module (memory (export "memory") 1))"#)
+                .unwrap()
+                .into_owned(),
+        ))
+        .await;
+        let names: Vec<ZomeName> = vec!["first".into(), "second".into()];
+        let zomes = names
+            .iter()
+            .map(|name| {
+                IntegrityZome::new(
+                    name.clone(),
+                    ZomeDef::Wasm(WasmZome::new(wasm.as_hash().clone())).into(),
+                )
+            })
+            .collect();
+        let mut dna_def = DnaDef::unique_from_zomes(zomes, vec![]);
+        dna_def.modifiers.properties = SerializedBytes::from(UnsafeBytes::from(vec![1, 2, 3]));
+        let dna_file = DnaFile::new(dna_def.clone(), vec![wasm.into_content()]).await;
+        let ribosome = Arc::new(
+            RealRibosomeFixturator::new(curve::DnaWith(dna_file))
+                .next()
+                .unwrap(),
+        );
+        assert_eq!(ribosome.dna_def().as_content(), &dna_def);
+
+        for name in names {
+            let zome = ribosome.dna_def().get_zome(&name).unwrap();
+            let mut call_context = CallContextFixturator::new(curve::ForZome(zome))
+                .next()
+                .unwrap();
+            call_context.host_context = HostContext::Validate(
+                ValidateHostAccessFixturator::new(Unpredictable)
+                    .next()
+                    .unwrap(),
+            );
+            let zome_info =
+                super::zome_info(ribosome.clone(), Arc::new(call_context), ()).unwrap();
+            assert_eq!(zome_info.name, name);
+        }
+    }
 
     #[tokio::test(flavor = "multi_thread")]
     async fn zome_info_test() {
@@ -211,6 +211,10 @@ static CONTEXT_MAP: ContextMap = Lazy::new(Default::default);
 
 static CONTEXT_KEY: AtomicU64 = AtomicU64::new(0);
 
+type ZomeFnsCache = Lazy<Mutex<HashMap<WasmHash, Arc<Vec<FunctionName>>>>>;
+/// The sorted exports of each wasm, which never change for the same hash.
+static ZOME_FNS: ZomeFnsCache = Lazy::new(Default::default);
+
 /// Create a key for the instance cache.
 /// It will be [WasmHash..DnaHash..context_key] all as bytes.
 fn instance_cache_key(wasm_hash: &WasmHash, dna_hash: &DnaHash, context_key: u64) -> [u8; 32] {
@@ -231,6 +235,123 @@ fn instance_cache_key(wasm_hash: &WasmHash, dna_hash: &DnaHash, context_key: u64
     bits
 }
 
+/// Call a guest function like [`holochain_wasmer_host::guest::call`], except
+/// that a trap is returned as the [`RuntimeError`] wasmer raised for it.
+/// `guest::call` turns it into a [`WasmErrorInner::CallError`] holding the
+/// formatted message, which loses the [`TrapCode`] and can't be told apart
+/// from the host failing with the same text.
+fn call_guest<I, O>(instance: Arc<Mutex<Instance>>, f: &str, input: I) -> Result<O, RuntimeError>
+where
+    I: serde::Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let call_error =
+        |e: &dyn std::fmt::Display| wasm_error!(WasmErrorInner::CallError(e.to_string()));
+    let instance = instance.lock();
+    let payload: Vec<u8> =
+        holochain_serialized_bytes::encode(&input).map_err(|e| wasm_error!(e))?;
+
+    // Get a pre-allocated guest pointer to write the input into.
+    let guest_input_length: i32 = payload
+        .len()
+        .try_into()
+        .map_err(|e: std::num::TryFromIntError| call_error(&e))?;
+    let guest_input_length_value = Value::I32(guest_input_length);
+    let (guest_input_ptr, guest_input_ptr_value): (GuestPtr, Value) = match instance
+        .exports
+        .get_function("__hc__allocate_1")
+        .map_err(|e| call_error(&e))?
+        .call(&[guest_input_length_value.clone()])
+        .map_err(|e| call_error(&e))?
+        .get(0)
+    {
+        Some(Value::I32(guest_input_ptr)) => (
+            (*guest_input_ptr)
+                .try_into()
+                .map_err(|e: std::num::TryFromIntError| call_error(&e))?,
+            Value::I32(*guest_input_ptr),
+        ),
+        _ => return Err(call_error(&"Not I32 return from __hc__allocate_1").into()),
+    };
+
+    let memory = || {
+        instance
+            .exports
+            .get_memory("memory")
+            .map_err(|_| wasm_error!(WasmErrorInner::Memory))
+    };
+    holochain_wasmer_host::guest::write_bytes(memory()?, guest_input_ptr, &payload)?;
+
+    let (guest_return_ptr, len) = match instance
+        .exports
+        .get_function(f)
+        .map_err(|e| call_error(&e))?
+        .call(&[guest_input_ptr_value, guest_input_length_value])
+    {
+        Ok(v) => match v.get(0) {
+            Some(Value::I64(i)) => split_u64(
+                (*i).try_into()
+                    .map_err(|e: std::num::TryFromIntError| wasm_error!(e))?,
+            )?,
+            _ => return Err(wasm_error!(WasmErrorInner::PointerMap).into()),
+        },
+        Err(e) => match e.downcast::<WasmError>() {
+            Ok(WasmError {
+                error: WasmErrorInner::HostShortCircuit(encoded),
+                ..
+            }) => {
+                return holochain_serialized_bytes::decode(&encoded)
+                    .map_err(|e| wasm_error!(e).into())
+            }
+            Ok(wasm_error) => return Err(wasm_error.into()),
+            Err(e) if e.clone().to_trap().is_some() => return Err(e),
+            Err(e) => return Err(call_error(&e).into()),
+        },
+    };
+
+    // The host must discard an instance that errors before this point.
+    let return_value: Result<O, WasmError> =
+        holochain_wasmer_host::guest::from_guest_ptr(memory()?, guest_return_ptr, len)?;
+
+    // Tell the guest we are finished with the return pointer's data.
+    instance
+        .exports
+        .get_function("__hc__deallocate_1")
+        .map_err(|e| call_error(&e))?
+        .call(&[
+            Value::I32(
+                guest_return_ptr
+                    .try_into()
+                    .map_err(|e: std::num::TryFromIntError| wasm_error!(e))?,
+            ),
+            Value::I32(
+                len.try_into()
+                    .map_err(|e: std::num::TryFromIntError| wasm_error!(e))?,
+            ),
+        ])
+        .map_err(|e| call_error(&format!("{:?}", e)))?;
+
+    return_value.map_err(|e| e.into())
+}
+
+/// Exports that are not zome functions a client can call.
+fn is_reserved_export(name: &FunctionName) -> bool {
+    const CALLBACKS: [&str; 8] = [
+        "entry_defs",
+        "genesis_self_check",
+        "init",
+        "migrate_agent",
+        "migrate_agent_close",
+        "migrate_agent_open",
+        "post_commit",
+        "validate",
+    ];
+    name.0.starts_with("__")
+        || name.0.starts_with("wasmer_metering_")
+        || name.0 == "memory"
+        || CALLBACKS.contains(&name.0.as_str())
+}
+
 /// Get the context key back from the end of the instance cache key.
 fn context_key_from_key(key: &[u8; 32]) -> u64 {
     let mut bits = [0u8; 8];
@@ -369,6 +490,53 @@ impl RealRibosome {
         )?)
     }
 
+    /// The functions a zome exports, sorted by name.
+    ///
+    /// Unless `include_reserved` is set, the callbacks that only the conductor
+    /// calls and the exports added by the compiler, the HDK and metering are
+    /// left out, leaving the functions a zome call can target.
+    pub fn list_zome_fns(
+        &self,
+        zome_name: &ZomeName,
+        include_reserved: bool,
+    ) -> RibosomeResult<Vec<FunctionName>> {
+        let zome = self.dna_def().get_zome(zome_name).map_err(DnaError::from)?;
+        let mut fns = match zome.zome_def() {
+            ZomeDef::Wasm(wasm_zome) => {
+                let cached = ZOME_FNS.lock().get(&wasm_zome.wasm_hash).cloned();
+                match cached {
+                    Some(fns) => fns.to_vec(),
+                    None => {
+                        let mut fns: Vec<_> = self
+                            .module(zome_name)?
+                            .info()
+                            .exports
+                            .iter()
+                            .map(|(name, _index)| FunctionName::new(name))
+                            .filter(|name| {
+                                name.0 != "__num_entry_types" && name.0 != "__num_link_types"
+                            })
+                            .collect();
+                        fns.sort();
+                        ZOME_FNS
+                            .lock()
+                            .insert(wasm_zome.wasm_hash.clone(), Arc::new(fns.clone()));
+                        fns
+                    }
+                }
+            }
+            ZomeDef::Inline { inline_zome, .. } => {
+                let mut fns = inline_zome.0.functions();
+                fns.sort();
+                fns
+            }
+        };
+        if !include_reserved {
+            fns.retain(|name| !is_reserved_export(name));
+        }
+        Ok(fns)
+    }
+
     pub fn wasm_cache_key(&self, zome_name: &ZomeName) -> Result<[u8; 32], DnaError> {
         // TODO: make this actually the hash of the wasm once we can do that
         // watch out for cache misses in the tests that make things slooow if you change this!
@@ -708,27 +876,7 @@ impl RibosomeT for RealRibosome {
                     }
                 }
             },
-            extern_fns: {
-                match zome.zome_def() {
-                    ZomeDef::Wasm(_) => {
-                        let module = self.module(zome.zome_name())?;
-
-                        let mut extern_fns: Vec<FunctionName> = module
-                            .info()
-                            .exports
-                            .iter()
-                            .filter(|(name, _)| {
-                                name.as_str() != "__num_entry_types"
-                                    && name.as_str() != "__num_link_types"
-                            })
-                            .map(|(name, _index)| FunctionName::new(name))
-                            .collect();
-                        extern_fns.sort();
-                        extern_fns
-                    }
-                    ZomeDef::Inline { inline_zome, .. } => inline_zome.0.functions(),
-                }
-            },
+            extern_fns: self.list_zome_fns(zome.zome_name(), true)?,
             zome_types,
         })
     }
@@ -742,12 +890,15 @@ impl RibosomeT for RealRibosome {
         zome: &Zome,
         to_call: &FunctionName,
     ) -> Result<Option<ExternIO>, RibosomeError> {
-        let call_context = CallContext {
-            zome: zome.clone(),
-            function_name: to_call.clone(),
+        let mut call_context = CallContext::new(
+            zome.clone(),
+            to_call.clone(),
             host_context,
-            auth: invocation.auth(),
-        };
+            invocation.auth(),
+        );
+        if let Some(call_id) = invocation.call_id() {
+            call_context.call_id = call_id;
+        }
 
         match zome.zome_def() {
             ZomeDef::Wasm(_) => {
@@ -759,7 +910,7 @@ impl RibosomeT for RealRibosome {
                     // because it builds guards against memory leaks and handles imports correctly
                     let (instance, context_key) = self.instance(call_context)?;
 
-                    let result: Result<ExternIO, RuntimeError> = holochain_wasmer_host::guest::call(
+                    let result: Result<ExternIO, RuntimeError> = call_guest(
                         instance.clone(),
                         to_call.as_ref(),
                         // be aware of this clone!
@@ -804,12 +955,12 @@ impl RibosomeT for RealRibosome {
 
     fn get_const_fn(&self, zome: &Zome, name: &str) -> Result<Option<i32>, RibosomeError> {
         // Create a blank context as this is not actually used.
-        let call_context = CallContext {
-            zome: zome.clone(),
-            function_name: name.into(),
-            host_context: HostContext::EntryDefs(EntryDefsHostAccess {}),
-            auth: super::InvocationAuth::LocalCallback,
-        };
+        let call_context = CallContext::new(
+            zome.clone(),
+            name.into(),
+            HostContext::EntryDefs(EntryDefsHostAccess {}),
+            super::InvocationAuth::LocalCallback,
+        );
 
         match zome.zome_def() {
             ZomeDef::Wasm(_) => {
@@ -874,7 +1025,19 @@ impl RibosomeT for RealRibosome {
         let guest_output: ExternIO = match self.call_iterator(host_access.into(), invocation).next()
         {
             Ok(Some((_zome, extern_io))) => extern_io,
-            Ok(None) => return Err(RibosomeError::ZomeFnNotExists(zome_name, fn_name)),
+            Ok(None) => {
+                let suggestions = self
+                    .list_zome_fns(&zome_name, false)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|name| strsim::levenshtein(&name.0, &fn_name.0) <= 2)
+                    .collect();
+                return Err(RibosomeError::ZomeFnNotExists {
+                    zome: zome_name,
+                    function: fn_name,
+                    available: suggestions,
+                });
+            }
             Err((_zome, ribosome_error)) => return Err(ribosome_error),
         };
 
@@ -1033,6 +1196,84 @@ pub mod wasm_test {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_zome_fns_test() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::HdkExtern).await;
+
+        let (dna_file, _, _) =
+            SweetDnaFile::unique_from_test_wasms(vec![TestWasm::HdkExtern]).await;
+        let ribosome = super::RealRibosome::new(dna_file).unwrap();
+        let zome_name = TestWasm::HdkExtern.coordinator_zome_name();
+        assert_eq!(
+            ribosome.list_zome_fns(&zome_name, false).unwrap(),
+            vec![
+                FunctionName::new("bar"),
+                FunctionName::new("foo"),
+                FunctionName::new("infallible"),
+            ]
+        );
+        assert!(ribosome
+            .list_zome_fns(&zome_name, true)
+            .unwrap()
+            .contains(&FunctionName::new("__hc__allocate_1")));
+
+        let err = conductor
+            .call_fallible::<_, String, _>(&alice, "fooo", ())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Did you mean: foo?"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_zome_fns_is_cached_by_wasm_hash() {
+        use holochain_types::prelude::{DnaFile, DnaWasm, DnaWasmHashed};
+        use holochain_wasmer_host::prelude::wat2wasm;
+        use std::sync::Arc;
+
+        let wasm = DnaWasmHashed::from_content(DnaWasm::from(
+            wat2wasm(
+                r#"(module
+                    (memory (export "memory") 1)
+                    (func (export "__hc__allocate_1") (param i32) (result i32) i32.const 0)
+                    (func (export "foo"))
+                    (func (export "bar"))
+                )"#
+                .as_bytes(),
+            )
+            .unwrap()
+            .into_owned(),
+        ))
+        .await;
+        let wasm_hash = wasm.as_hash().clone();
+        let zome = CoordinatorZome::new(
+            "cached".into(),
+            ZomeDef::Wasm(WasmZome::new(wasm_hash.clone())).into(),
+        );
+        let dna_file = DnaFile::new(
+            DnaDef::unique_from_zomes(vec![], vec![zome]),
+            vec![wasm.into_content()],
+        )
+        .await;
+        let ribosome = super::RealRibosome::new(dna_file).unwrap();
+        let zome_name = ZomeName::from("cached");
+
+        let fns = vec![FunctionName::new("bar"), FunctionName::new("foo")];
+        assert_eq!(ribosome.list_zome_fns(&zome_name, false).unwrap(), fns);
+        assert!(super::ZOME_FNS.lock().contains_key(&wasm_hash));
+
+        // Later lookups are answered from the cache rather than the module.
+        super::ZOME_FNS
+            .lock()
+            .insert(wasm_hash, Arc::new(vec![FunctionName::new("baz")]));
+        assert_eq!(
+            ribosome.list_zome_fns(&zome_name, false).unwrap(),
+            vec![FunctionName::new("baz")]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn wasm_tooling_test() {
         observability::test_run().ok();
@@ -8,6 +8,7 @@ use super::sys_validation_workflow::validation_query;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
@@ -27,12 +28,14 @@ use holochain_state::host_fn_workspace::HostFnWorkspaceRead;
 use holochain_state::prelude::*;
 use holochain_types::db_cache::DhtDbQueryCache;
 use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
 use holochain_zome_types::op::EntryCreationAction;
 use holochain_zome_types::op::Op;
 use rusqlite::Transaction;
 use std::collections::HashSet;
 use tracing::*;
 pub use types::Outcome;
+use wasmer_types::TrapCode;
 
 #[cfg(todo_redo_old_tests)]
 mod network_call_tests;
@@ -593,6 +596,47 @@ fn store_record_zomes_to_invoke(
     }
 }
 
+/// Whether a wasm trap follows from the wasm and its input alone, so every
+/// validator that runs the validate callback on the op traps the same way.
+///
+/// Running out of metering traps as `UnreachableCodeReached`. The metering
+/// limit and cost of each instruction are fixed in the conductor and the
+/// points are reset before every call. A wasm that can't allocate memory also
+/// ends up there. Wasmer reserves the address space of each memory up front,
+/// so growing a memory fails at the maximum declared by the wasm.
+///
+/// A stack overflow is left out because how deep the stack can grow depends
+/// on the native stack of the thread that runs the wasm.
+fn is_deterministic_trap(trap: TrapCode) -> bool {
+    match trap {
+        TrapCode::HeapAccessOutOfBounds
+        | TrapCode::HeapMisaligned
+        | TrapCode::TableAccessOutOfBounds
+        | TrapCode::OutOfBounds
+        | TrapCode::IndirectCallToNull
+        | TrapCode::BadSignature
+        | TrapCode::IntegerOverflow
+        | TrapCode::IntegerDivisionByZero
+        | TrapCode::BadConversionToInteger
+        | TrapCode::UnreachableCodeReached
+        | TrapCode::UnalignedAtomic => true,
+        TrapCode::StackOverflow => false,
+    }
+}
+
+/// The ribosome already turns a guest error returned from a validate callback
+/// into [`ValidateResult::Invalid`], so the errors left here are traps and
+/// errors raised by the host. A trap that every validator would hit is the
+/// op's fault, so its name is returned to reject the op with.
+/// Only wasmer sets a trap code, when the wasm itself traps, so an error
+/// raised by the host is handed back whatever its message says.
+fn deterministic_trap_reason(runtime_error: RuntimeError) -> Result<String, RuntimeError> {
+    match runtime_error.clone().to_trap() {
+        Some(trap) if is_deterministic_trap(trap) => Ok(trap.to_string()),
+        _ => Err(runtime_error),
+    }
+}
+
 #[async_recursion::async_recursion]
 async fn run_validation_callback_inner<R>(
     invocation: ValidateInvocation,
@@ -605,10 +649,18 @@ async fn run_validation_callback_inner<R>(
 where
     R: RibosomeT,
 {
-    let validate_result = ribosome.run_validate(
+    let validate_result = match ribosome.run_validate(
         ValidateHostAccess::new(workspace_read.clone(), network.clone()),
         invocation.clone(),
-    )?;
+    ) {
+        Err(RibosomeError::WasmRuntimeError(runtime_error)) => {
+            return match deterministic_trap_reason(runtime_error) {
+                Ok(reason) => Ok(Outcome::Rejected(reason)),
+                Err(runtime_error) => Err(RibosomeError::WasmRuntimeError(runtime_error).into()),
+            };
+        }
+        result => result?,
+    };
     match validate_result {
         ValidateResult::Valid => Ok(Outcome::Accepted),
         ValidateResult::Invalid(reason) => Ok(Outcome::Rejected(reason)),
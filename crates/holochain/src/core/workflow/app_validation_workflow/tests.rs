@@ -28,6 +28,122 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::time::Duration;
 
+#[tokio::test(flavor = "multi_thread")]
+async fn host_errors_in_validate_callbacks_do_not_reject_the_op() {
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::fixt::HostFnWorkspaceReadFixturator;
+    use ::fixt::prelude::*;
+    use arbitrary::Arbitrary;
+    use holochain_p2p::HolochainP2pDnaFixturator;
+    use holochain_wasmer_host::prelude::*;
+    use matches::assert_matches;
+
+    let run = |error: fn() -> RuntimeError| async move {
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_validate()
+            .returning(move |_, _| Err(RibosomeError::WasmRuntimeError(error())));
+        let mut u = arbitrary::Unstructured::new(&[0; 1000]);
+        super::run_validation_callback_inner(
+            ValidateInvocation::arbitrary(&mut u).unwrap(),
+            &ribosome,
+            fixt!(HostFnWorkspaceRead),
+            fixt!(HolochainP2pDna),
+            (Default::default(), 0),
+            Default::default(),
+        )
+        .await
+    };
+
+    // Only wasmer sets a trap code, so errors from the host are handed back
+    // whatever their message says, including one that reads like a trap.
+    assert_matches!(
+        run(|| wasm_error!(WasmErrorInner::CallError(
+            "RuntimeError: integer divide by zero\n    at <unnamed> (<module>[2]:0x4f)".into()
+        ))
+        .into())
+        .await,
+        Err(_)
+    );
+    assert_matches!(run(|| RuntimeError::new("unreachable")).await, Err(_));
+    assert_matches!(
+        run(|| wasm_error!(WasmErrorInner::Host("db gone".into())).into()).await,
+        Err(_)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn trapping_wasm_validate_callbacks_reject_the_op() {
+    use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
+    use crate::core::ribosome::real_ribosome::RealRibosome;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::core::workflow::app_validation_workflow::Outcome;
+    use crate::fixt::HostFnWorkspaceReadFixturator;
+    use ::fixt::prelude::*;
+    use arbitrary::Arbitrary;
+    use holochain_p2p::HolochainP2pDnaFixturator;
+    use holochain_wasmer_host::prelude::wat2wasm;
+    use matches::assert_matches;
+
+    // The least a wasm needs for the host to call its validate callback.
+    let run = |validate_body: &'static str| async move {
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "__hc__allocate_1") (param i32) (result i32) i32.const 0)
+                (func (export "__hc__deallocate_1") (param i32 i32))
+                (func (export "validate") (param i32 i32) (result i64) {})
+            )"#,
+            validate_body
+        );
+        let wasm = DnaWasmHashed::from_content(DnaWasm::from(
+            wat2wasm(wat.as_bytes()).unwrap().into_owned(),
+        ))
+        .await;
+        let zome = IntegrityZome::new(
+            "trap".into(),
+            ZomeDef::Wasm(WasmZome::new(wasm.as_hash().clone())).into(),
+        );
+        let dna_file = DnaFile::new(
+            DnaDef::unique_from_zomes(vec![zome], vec![]),
+            vec![wasm.into_content()],
+        )
+        .await;
+        let ribosome = RealRibosome::new(dna_file).unwrap();
+        let mut u = arbitrary::Unstructured::new(&[0; 1000]);
+        super::run_validation_callback_inner(
+            ValidateInvocation::new(ZomesToInvoke::AllIntegrity, &Op::arbitrary(&mut u).unwrap())
+                .unwrap(),
+            &ribosome,
+            fixt!(HostFnWorkspaceRead),
+            fixt!(HolochainP2pDna),
+            (Default::default(), 0),
+            Default::default(),
+        )
+        .await
+    };
+
+    // Only the trap's name is kept as the reason, not the wasm backtrace.
+    assert_matches!(
+        run("unreachable").await,
+        Ok(Outcome::Rejected(reason)) if reason == "unreachable"
+    );
+    assert_matches!(
+        run("i32.const 1 i32.const 0 i32.div_u drop i64.const 0").await,
+        Ok(Outcome::Rejected(reason)) if reason == "int_divz"
+    );
+    // Running out of metering.
+    assert_matches!(
+        run("(loop br 0) i64.const 0").await,
+        Ok(Outcome::Rejected(reason)) if reason == "unreachable"
+    );
+    // How deep the stack can grow differs between validators, so the
+    // validate callback recursing until it overflows is not a rejection.
+    assert_matches!(run("local.get 0 local.get 1 call 2").await, Err(_));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn app_validation_workflow_test() {
     observability::test_run().ok();
@@ -213,6 +329,108 @@ async fn test_private_entries_are_passed_to_validation_only_when_authored_with_f
     )
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn ops_rejected_by_an_authority_are_not_served_by_it() {
+    use holochain_types::inline_zome::InlineEntryTypes;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    #[derive(Debug, Serialize, Deserialize, SerializedBytes)]
+    struct Content(String);
+
+    fn content_entry(content: &str) -> Entry {
+        Entry::app(Content(content.into()).try_into().unwrap()).unwrap()
+    }
+
+    // Inline zomes can't trap, so bob's validate callback rejecting the "bad"
+    // entry after alice has accepted it stands in for a trap only bob's
+    // node hits. Both end in the same rejected outcome.
+    let reject_bad = Arc::new(AtomicBool::new(false));
+    let reject_bad_2 = reject_bad.clone();
+
+    let zomeset = InlineZomeSet::new_unique(
+        [("integrity", InlineEntryTypes::entry_defs(), 0)],
+        ["coordinator"],
+    )
+    .function("integrity", "validate", move |_h, op: Op| {
+        let entry = match &op {
+            Op::StoreEntry(StoreEntry { entry, .. }) => Some(entry),
+            Op::StoreRecord(StoreRecord { record }) => record.entry().as_option(),
+            _ => None,
+        };
+        if reject_bad_2.load(Ordering::SeqCst) && entry == Some(&content_entry("bad")) {
+            Ok(ValidateResult::Invalid("bad".into()))
+        } else {
+            Ok(ValidateResult::Valid)
+        }
+    })
+    .function("coordinator", "create", |h, content: String| {
+        let entry = content_entry(&content);
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        h.create(CreateInput::new(
+            InlineZomeSet::get_entry_location(&h, InlineEntryTypes::A),
+            EntryVisibility::Public,
+            entry,
+            ChainTopOrdering::default(),
+        ))?;
+        Ok(entry_hash)
+    })
+    .function("coordinator", "get", |h, hash: EntryHash| {
+        Ok(
+            h.get(vec![GetInput::new(hash.into(), GetOptions::content())])?
+                .pop()
+                .unwrap(),
+        )
+    });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomeset).await;
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let apps = conductors
+        .setup_app("test_app", &[dna_file.clone()])
+        .await
+        .unwrap();
+    let ((alice,), (bob,)) = apps.into_tuples();
+
+    let bad: EntryHash = conductors[0]
+        .call(&alice.zome("coordinator"), "create", "bad".to_string())
+        .await;
+    let good: EntryHash = conductors[0]
+        .call(&alice.zome("coordinator"), "create", "good".to_string())
+        .await;
+    // Alice has validated her own ops before bob starts rejecting.
+    consistency_10s([&alice]).await;
+
+    reject_bad.store(true, Ordering::SeqCst);
+    conductors.exchange_peer_info().await;
+    consistency_10s([&alice, &bob]).await;
+
+    let rejected: usize = fresh_reader_test(bob.dht_db().clone(), |txn| {
+        txn.query_row(
+            "SELECT count(hash) FROM DhtOp WHERE validation_status = :rejected",
+            named_params! { ":rejected": ValidationStatus::Rejected },
+            |row| row.get(0),
+        )
+        .unwrap()
+    });
+    assert!(rejected > 0);
+
+    // Bob holds the ops as an authority, so bob's node answers from what it
+    // has validated and does not go to alice for the rejected entry.
+    let from_bob: Option<Record> = conductors[1]
+        .call(&bob.zome("coordinator"), "get", bad.clone())
+        .await;
+    assert!(from_bob.is_none());
+    let from_bob: Option<Record> = conductors[1]
+        .call(&bob.zome("coordinator"), "get", good)
+        .await;
+    assert!(from_bob.is_some());
+    let from_alice: Option<Record> = conductors[0]
+        .call(&alice.zome("coordinator"), "get", bad)
+        .await;
+    assert!(from_alice.is_some());
+}
+
 const SELECT: &'static str = "SELECT count(hash) FROM DhtOp WHERE";
 
 // These are the expected invalid ops
@@ -238,6 +238,48 @@ pub mod tests {
             );
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn genesis_self_check_rejection_leaves_chain_empty() {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let vault = test_db.to_db();
+        let dna = fake_dna_file("a");
+        let author = fake_agent_pubkey_1();
+
+        let workspace = GenesisWorkspace::new(vault.clone(), dht_db.to_db()).unwrap();
+        let mut api = MockCellConductorApiT::new();
+        api.expect_keystore().return_const(test_keystore());
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_genesis_self_check()
+            .returning(|_, _| Ok(GenesisSelfCheckResult::Invalid("no entry".to_string())));
+        let dna_def = DnaDefHashed::from_content_sync(dna.dna_def().clone());
+        ribosome.expect_dna_def().return_const(dna_def);
+        let args = GenesisWorkflowArgs {
+            dna_file: dna.clone(),
+            agent_pubkey: author.clone(),
+            membrane_proof: None,
+            ribosome,
+            dht_db_cache,
+            chc: None,
+        };
+        assert_matches!(
+            genesis_workflow(workspace, api, args).await,
+            Err(WorkflowError::GenesisFailure(reason)) if reason == "no entry"
+        );
+
+        let count: usize = vault
+            .async_reader(|txn| {
+                DatabaseResult::Ok(
+                    txn.query_row("SELECT COUNT(*) FROM Action", [], |row| row.get(0))?,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
 }
 
 /* TODO: update and rewrite as proper rust docs
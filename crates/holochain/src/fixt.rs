@@ -33,6 +33,7 @@ use holochain_state::test_utils::test_keystore;
 use holochain_types::db_cache::DhtDbQueryCache;
 use holochain_types::prelude::*;
 use holochain_wasm_test_utils::TestWasm;
+use holochain_wasm_test_utils::TestWasmPair;
 use rand::seq::IteratorRandom;
 use rand::thread_rng;
 use rand::Rng;
@@ -74,6 +75,67 @@ impl Iterator for RealRibosomeFixturator<curve::Zomes> {
     }
 }
 
+impl Iterator for RealRibosomeFixturator<curve::ZomesNamed> {
+    type Item = RealRibosome;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut integrity_zomes: Vec<IntegrityZome> = Vec::new();
+        let mut coordinator_zomes = Vec::new();
+        let mut wasms = Vec::new();
+        for (zome_name, test_wasm) in self.0.curve.0.clone() {
+            let TestWasmPair::<IntegrityZome, CoordinatorZome> {
+                integrity,
+                coordinator,
+            } = test_wasm.into();
+            let TestWasmPair::<DnaWasm> {
+                integrity: integrity_wasm,
+                coordinator: coordinator_wasm,
+            } = test_wasm.into();
+            // The same wasm may be used by several coordinators.
+            if !integrity_zomes
+                .iter()
+                .any(|z| z.zome_name() == integrity.zome_name())
+            {
+                integrity_zomes.push(integrity);
+                wasms.push(integrity_wasm);
+            }
+            coordinator_zomes.push(CoordinatorZome::new(zome_name, coordinator.into_inner().1));
+            wasms.push(coordinator_wasm);
+        }
+        let uuid = StringFixturator::new(Unpredictable).next().unwrap();
+        let (dna_file, _, coordinator_zomes) = tokio_helper::block_forever_on(async move {
+            SweetDnaFile::from_zomes(
+                uuid,
+                integrity_zomes,
+                coordinator_zomes,
+                wasms,
+                Default::default(),
+            )
+            .await
+        });
+
+        let ribosome = RealRibosome::new(dna_file).unwrap();
+
+        // warm the module cache for each wasm in the ribosome
+        for zome in coordinator_zomes {
+            ribosome.module(zome.zome_name()).unwrap();
+        }
+
+        self.0.index += 1;
+
+        Some(ribosome)
+    }
+}
+
+impl Iterator for RealRibosomeFixturator<curve::DnaWith> {
+    type Item = RealRibosome;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.index += 1;
+        Some(RealRibosome::new(self.0.curve.0.clone()).unwrap())
+    }
+}
+
 fixturator!(
     DnaWasm;
     // note that an empty wasm will not compile
@@ -428,6 +490,17 @@ fixturator!(
     constructor fn new(Zome, FunctionName, HostContext, InvocationAuth);
 );
 
+impl Iterator for CallContextFixturator<curve::ForZome> {
+    type Item = CallContext;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome = self.0.curve.0.clone();
+        self.0.index += 1;
+        Some(call_context)
+    }
+}
+
 fixturator!(
     ZomeCallInvocation;
     curve Empty ZomeCallInvocation {
@@ -440,6 +513,7 @@ fixturator!(
         signature: SignatureFixturator::new(Empty).next().unwrap(),
         nonce: Nonce256Bits::try_from(ThirtyTwoBytesFixturator::new(Empty).next().unwrap()).unwrap(),
         expires_at: TimestampFixturator::new(Empty).next().unwrap(),
+        call_id: U64Fixturator::new(Empty).next().unwrap(),
     };
     curve Unpredictable ZomeCallInvocation {
         cell_id: CellIdFixturator::new(Unpredictable).next().unwrap(),
@@ -452,6 +526,7 @@ fixturator!(
         nonce: Nonce256Bits::try_from(ThirtyTwoBytesFixturator::new(Unpredictable).next().unwrap()).unwrap(),
         // @todo should this be less predictable?
         expires_at: (Timestamp::now() + std::time::Duration::from_secs(10)).unwrap(),
+        call_id: U64Fixturator::new(Unpredictable).next().unwrap(),
     };
     curve Predictable ZomeCallInvocation {
         cell_id: CellIdFixturator::new_indexed(Predictable, get_fixt_index!())
@@ -476,6 +551,7 @@ fixturator!(
         nonce: Nonce256Bits::try_from(ThirtyTwoBytesFixturator::new_indexed(Predictable, get_fixt_index!()).next().unwrap()).unwrap(),
         // @todo should this be more predictable?
         expires_at: (Timestamp::now() + std::time::Duration::from_secs(10)).unwrap(),
+        call_id: U64Fixturator::new_indexed(Predictable, get_fixt_index!()).next().unwrap(),
     };
 );
 
@@ -1,3 +1,14 @@
+use holochain_types::prelude::*;
 use holochain_wasm_test_utils::TestWasm;
 
 pub struct Zomes(pub Vec<TestWasm>);
+
+/// Each test wasm's coordinator zome under the given name, for DNAs that
+/// need specific zome names or the same wasm more than once.
+pub struct ZomesNamed(pub Vec<(ZomeName, TestWasm)>);
+
+/// Exactly this DNA, zomes, properties and wasms included.
+pub struct DnaWith(pub DnaFile);
+
+/// A call into this zome.
+pub struct ForZome(pub Zome);
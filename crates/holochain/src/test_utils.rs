@@ -904,6 +904,7 @@ where
         signature,
         nonce,
         expires_at,
+        call_id: crate::core::ribosome::fresh_call_id(),
     })
 }
 
@@ -68,6 +68,93 @@ async fn test_publish() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Test the whole loop between two conductors: alice's commit is published
+/// to and integrated by bobbo, bobbo can `get` it, and alice can read it
+/// back through bobbo with `call_remote` once bobbo grants access.
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn publish_get_and_call_remote() -> anyhow::Result<()> {
+    use holochain::test_utils::{consistency_10s, inline_zomes::simple_create_read_zome};
+
+    let _g = observability::test_run().ok();
+
+    let zome = simple_create_read_zome()
+        .function("grant_read", |api, ()| {
+            let mut functions = BTreeSet::new();
+            functions.insert((api.zome_info(()).unwrap().name, "read".into()));
+            let hash = api.create(CreateInput::new(
+                EntryDefLocation::CapGrant,
+                EntryVisibility::Private,
+                Entry::CapGrant(CapGrantEntry {
+                    tag: "".into(),
+                    // empty access converts to unrestricted
+                    access: ().into(),
+                    functions: GrantedFunctions::Listed(functions),
+                }),
+                ChainTopOrdering::default(),
+            ))?;
+            Ok(hash)
+        })
+        .function(
+            "read_remotely",
+            |api, (agent, hash): (AgentPubKey, ActionHash)| {
+                let zome_name = api.zome_info(()).unwrap().name;
+                let response = api
+                    .call(vec![Call::new(
+                        CallTarget::NetworkAgent(agent),
+                        zome_name,
+                        "read".into(),
+                        None,
+                        ExternIO::encode(hash).unwrap(),
+                    )])?
+                    .pop()
+                    .unwrap();
+                Ok(response)
+            },
+        );
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(("simple", zome)).await;
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let hash: ActionHash = conductors[0]
+        .call(&alice.zome("simple"), "create", ())
+        .await;
+    let _: ActionHash = conductors[1]
+        .call(&bobbo.zome("simple"), "grant_read", ())
+        .await;
+
+    // Polls the integrated op counts rather than sleeping.
+    consistency_10s([&alice, &bobbo]).await;
+
+    let record: Option<Record> = conductors[1]
+        .call(&bobbo.zome("simple"), "read", hash.clone())
+        .await;
+    let record = record.expect("Record was None: bobbo couldn't `get` it");
+    assert_eq!(record.action().author(), alice.agent_pubkey());
+
+    let response: ZomeCallResponse = conductors[0]
+        .call(
+            &alice.zome("simple"),
+            "read_remotely",
+            (bobbo.agent_pubkey().clone(), hash.clone()),
+        )
+        .await;
+    let remote_record: Option<Record> = match response {
+        ZomeCallResponse::Ok(output) => output.decode()?,
+        response => panic!("call_remote to bobbo failed: {:?}", response),
+    };
+    assert_eq!(
+        remote_record.expect("bobbo returned None").action_address(),
+        &hash
+    );
+
+    Ok(())
+}
+
 #[cfg(feature = "test_utils")]
 #[tokio::test(flavor = "multi_thread")]
 async fn multi_conductor() -> anyhow::Result<()> {
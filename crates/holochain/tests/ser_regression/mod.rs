@@ -129,7 +129,7 @@ async fn ser_regression_test() {
     let response = app_api.handle_app_request(request).await;
 
     let _channel_hash: EntryHash = match response {
-        AppResponse::ZomeCalled(r) => r.decode().unwrap(),
+        AppResponse::ZomeCalled { response: r, .. } => r.decode().unwrap(),
         _ => unreachable!(),
     };
 
@@ -173,7 +173,7 @@ async fn ser_regression_test() {
     let response = app_api.handle_app_request(request).await;
 
     let _msg_hash: EntryHash = match response {
-        AppResponse::ZomeCalled(r) => r.decode().unwrap(),
+        AppResponse::ZomeCalled { response: r, .. } => r.decode().unwrap(),
         _ => unreachable!(),
     };
 
@@ -483,6 +483,9 @@ async fn mock_network_sharded_gossip() {
                             debug!("CallRemoteMulti")
                         }
                         holochain_p2p::WireMessage::CallRemote { .. } => debug!("CallRemote"),
+                        holochain_p2p::WireMessage::CallRemoteV2 { .. } => {
+                            debug!("CallRemoteV2")
+                        }
                         holochain_p2p::WireMessage::PublishCountersign { .. } => {
                             debug!("PublishCountersign")
                         }
@@ -1003,6 +1006,9 @@ async fn mock_network_sharding() {
                             debug!("CallRemoteMulti")
                         }
                         holochain_p2p::WireMessage::CallRemote { .. } => debug!("CallRemote"),
+                        holochain_p2p::WireMessage::CallRemoteV2 { .. } => {
+                            debug!("CallRemoteV2")
+                        }
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
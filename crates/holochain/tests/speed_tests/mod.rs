@@ -240,7 +240,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
         )
         .await
         .unwrap();
-        assert_matches!(response, AppResponse::ZomeCalled(_));
+        assert_matches!(response, AppResponse::ZomeCalled { .. });
         let invocation = anchor_invocation("bobbo".to_string(), bob_cell_id.clone(), i)
             .await
             .unwrap();
@@ -252,7 +252,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
         )
         .await
         .unwrap();
-        assert_matches!(response, AppResponse::ZomeCalled(_));
+        assert_matches!(response, AppResponse::ZomeCalled { .. });
     }
 
     let mut alice_done = false;
@@ -278,7 +278,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
             .await
             .unwrap();
             let hashes: EntryHashes = match response {
-                AppResponse::ZomeCalled(r) => r.decode().unwrap(),
+                AppResponse::ZomeCalled { response: r, .. } => r.decode().unwrap(),
                 _ => unreachable!(),
             };
             bobbo_done = hashes.0.len() == num;
@@ -302,7 +302,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
             .await
             .unwrap();
             let hashes: EntryHashes = match response {
-                AppResponse::ZomeCalled(r) => r.decode().unwrap(),
+                AppResponse::ZomeCalled { response: r, .. } => r.decode().unwrap(),
                 _ => unreachable!(),
             };
             alice_done = hashes.0.len() == num;
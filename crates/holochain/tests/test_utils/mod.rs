@@ -155,7 +155,7 @@ pub async fn call_zome_fn<S>(
     let response = app_tx.request(request);
     let call_response = check_timeout(response, 6000).await;
     trace!(?call_response);
-    assert_matches!(call_response, AppResponse::ZomeCalled(_));
+    assert_matches!(call_response, AppResponse::ZomeCalled { .. });
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
@@ -345,6 +345,7 @@ pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
         dpki: None,
         keystore: KeystoreConfig::DangerTestKeystore,
         db_sync_strategy: DbSyncStrategy::default(),
+        db_quota: None,
         chc_namespace: None,
     }
 }
@@ -244,3 +244,59 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_reports_forks() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&db.to_db(), hash_op);
+    }
+
+    // A second, different action at seq 10 on top of the same previous action.
+    let original = td.hash_ops[10].action();
+    assert_eq!(original.action_seq(), 10);
+    let mut fork = original.clone();
+    if let Action::Create(create) = &mut fork {
+        create.timestamp = Timestamp::from_micros(create.timestamp.as_micros().wrapping_add(1));
+    }
+    fill_db(
+        &db.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            td.hash_ops[10].signature().clone(),
+            fork.clone(),
+        )),
+    );
+
+    let options = actor::GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+    let result = handle_get_agent_activity(
+        db.to_db().into(),
+        td.agent.clone(),
+        QueryFilter::new(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+
+    let fork_status = match result.status {
+        ChainStatus::Forked(fork_status) => fork_status,
+        status => panic!("Expected a forked chain but got {:?}", status),
+    };
+    assert_eq!(fork_status.fork_seq, 10);
+    let mut branches = vec![fork_status.first_action, fork_status.second_action];
+    branches.sort();
+    let mut expected = vec![
+        ActionHash::with_data_sync(&original),
+        ActionHash::with_data_sync(&fork),
+    ];
+    expected.sort();
+    assert_eq!(branches, expected);
+}
@@ -37,6 +37,7 @@ use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_sqlite::rusqlite::Transaction;
+use holochain_state::cache::mark_accessed;
 use holochain_state::host_fn_workspace::HostFnStores;
 use holochain_state::host_fn_workspace::HostFnWorkspace;
 use holochain_state::mutations::set_validation_status;
@@ -229,6 +230,7 @@ where
         let op_order = OpOrder::new(op_light.get_type(), action.action().timestamp());
         let timestamp = action.action().timestamp();
         insert_action(txn, action)?;
+        mark_accessed(txn, &action.as_hash().clone().into(), Timestamp::now())?;
         insert_op_lite(txn, op_light, op_hash, &op_order, &timestamp)?;
         if let Some(status) = validation_status {
             set_validation_status(txn, op_hash, *status)?;
@@ -259,7 +261,7 @@ where
             let RegisterAgentActivity {
                 action:
                     SignedHashed {
-                        hashed: HoloHashed { content, .. },
+                        hashed: HoloHashed { content, hash },
                         signature,
                     },
                 ..
@@ -267,6 +269,7 @@ where
             let op =
                 DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(signature, content));
             insert_op(txn, &op)?;
+            mark_accessed(txn, &hash.into(), Timestamp::now())?;
             // We set the integrated to for the cache so it can match the
             // same query as the vault. This can also be used for garbage collection.
             set_when_integrated(txn, op.as_hash(), Timestamp::now())?;
@@ -356,6 +359,19 @@ where
         }
     }
 
+    /// Note that the data at this hash was just read, so that cache eviction
+    /// keeps it over data read less recently. Reads don't fail if this does.
+    async fn mark_accessed_in_cache(&self, hash: AnyDhtHash) {
+        if let Some(cache) = self.cache.as_ref() {
+            if let Err(e) = cache
+                .async_commit(move |txn| mark_accessed(txn, &hash, Timestamp::now()))
+                .await
+            {
+                warn!(?e, "Failed to record a read from the cache");
+            }
+        }
+    }
+
     /// Fetch a Record from the network, caching and returning the results
     #[instrument(skip(self, options))]
     pub async fn fetch_record(
@@ -528,6 +544,7 @@ where
             })
             .await?;
         if result.is_some() {
+            self.mark_accessed_in_cache(hash.into()).await;
             return Ok(result.map(EntryHashed::from_content_sync));
         }
         options.request_type = holochain_p2p::event::GetRequest::Pending;
@@ -563,6 +580,7 @@ where
             })
             .await?;
         if result.is_some() {
+            self.mark_accessed_in_cache(hash.into()).await;
             return Ok(result);
         }
         options.request_type = holochain_p2p::event::GetRequest::Pending;
@@ -595,6 +613,7 @@ where
             })
             .await?;
         if result.is_some() {
+            self.mark_accessed_in_cache(hash).await;
             return Ok(result);
         }
         options.request_type = holochain_p2p::event::GetRequest::Pending;
@@ -632,6 +651,7 @@ where
             let results = self.cascading(query.clone()).await?;
             // We got a result so can short circuit.
             if results.is_some() {
+                self.mark_accessed_in_cache(entry_hash.clone().into()).await;
                 return Ok(results);
             // We didn't get a result so if we are either authoring
             // or the authority there's nothing left to do.
@@ -675,6 +695,8 @@ where
             let results = self.cascading(query.clone()).await?;
             // We got a result so can short circuit.
             if results.is_some() {
+                self.mark_accessed_in_cache(action_hash.clone().into())
+                    .await;
                 return Ok(results);
             // We didn't get a result so if we are either authoring
             // or the authority there's nothing left to do.
@@ -719,6 +741,8 @@ where
             let results = self.cascading(query.clone()).await?;
             // We got a result so can short circuit.
             if results.is_some() {
+                self.mark_accessed_in_cache(action_hash.clone().into())
+                    .await;
                 return Ok(results);
             // We didn't get a result so if we are either authoring
             // or the authority there's nothing left to do.
@@ -757,6 +781,7 @@ where
             let results = self.cascading(query.clone()).await?;
             // We got a result so can short circuit.
             if results.is_some() {
+                self.mark_accessed_in_cache(entry_hash.clone().into()).await;
                 return Ok(results);
             // We didn't get a result so if we are either authoring
             // or the authority there's nothing left to do.
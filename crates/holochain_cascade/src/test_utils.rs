@@ -290,6 +290,7 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         _payload: holochain_zome_types::ExternIO,
         _nonce: Nonce256Bits,
         _expires_at: Timestamp,
+        _call_id: u64,
     ) -> actor::HolochainP2pResult<holochain_serialized_bytes::SerializedBytes> {
         todo!()
     }
@@ -492,6 +493,7 @@ impl HolochainP2pDnaT for MockNetwork {
         _payload: holochain_zome_types::ExternIO,
         _nonce: Nonce256Bits,
         _expires_at: Timestamp,
+        _call_id: u64,
     ) -> actor::HolochainP2pResult<holochain_serialized_bytes::SerializedBytes> {
         todo!()
     }
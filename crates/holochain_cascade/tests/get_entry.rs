@@ -508,3 +508,73 @@ async fn test_pending_data_isnt_returned() {
 
     assert_can_retrieve(&td_entry, &mut cascade, GetOptions::latest()).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recently_read_cache_records_survive_eviction() {
+    use holochain_state::cache::evict_least_recently_used;
+
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td_records: Vec<_> = std::iter::repeat_with(RecordTestData::create)
+        .take(3)
+        .collect();
+    for td_record in &td_records {
+        fill_db(&authority.to_db(), td_record.any_store_record_op.clone());
+    }
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().into()]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    // Fetch every record into the cache, then read the first from the cache.
+    for td_record in &td_records {
+        cascade
+            .dht_get(
+                td_record.any_action_hash.clone().into(),
+                GetOptions::latest(),
+            )
+            .await
+            .unwrap()
+            .expect("Failed to fetch record");
+    }
+    cascade
+        .dht_get(
+            td_records[0].any_action_hash.clone().into(),
+            GetOptions::content(),
+        )
+        .await
+        .unwrap()
+        .expect("Failed to read record from the cache");
+
+    // Going one byte over budget evicts the action accessed longest ago,
+    // which is the second record's.
+    let report = evict_least_recently_used(&cache.to_db(), u64::MAX)
+        .await
+        .unwrap();
+    let report = evict_least_recently_used(&cache.to_db(), report.bytes - 1)
+        .await
+        .unwrap();
+    assert_eq!(report.actions, 1);
+
+    let cached: Vec<bool> = cache.to_db().conn().unwrap().with_reader_test(|txn| {
+        td_records
+            .iter()
+            .map(|td_record| {
+                txn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM Action WHERE hash = ?)",
+                    [&td_record.any_action_hash],
+                    |row| row.get(0),
+                )
+                .unwrap()
+            })
+            .collect()
+    });
+    assert_eq!(cached, vec![true, false, true]);
+}
@@ -35,7 +35,7 @@ pub enum AppRequest {
     ///
     /// # Returns
     ///
-    /// [`AppResponse::ZomeCalled`]
+    /// [`AppResponse::ZomeCalled`], or [`AppResponse::ZomeCallFailed`] if the call failed
     CallZome(Box<ZomeCall>),
 
     /// Clone a DNA (in the biological sense), thus creating a new `Cell`.
@@ -92,7 +92,22 @@ pub enum AppResponse {
     /// to decode this response back into the data provided by the zome using a [msgpack] library to utilize it.
     ///
     /// [msgpack]: https://msgpack.org/
-    ZomeCalled(Box<ExternIO>),
+    ZomeCalled {
+        /// The id the conductor ran the call under, which its logs record
+        /// the call's traces with.
+        call_id: u64,
+        /// The output of the zome function.
+        response: Box<ExternIO>,
+    },
+
+    /// The response to an [`AppRequest::CallZome`] that failed.
+    ZomeCallFailed {
+        /// The id the conductor ran the call under, which its logs record
+        /// the call's traces with.
+        call_id: u64,
+        /// Why the call failed.
+        error: ExternalApiWireError,
+    },
 
     /// The successful response to an [`AppRequest::CreateCloneCell`].
     ///
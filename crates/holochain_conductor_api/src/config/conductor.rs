@@ -7,6 +7,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 mod admin_interface_config;
+mod db_quota_config;
 mod dpki_config;
 #[allow(missing_docs)]
 mod error;
@@ -17,6 +18,7 @@ pub mod paths;
 pub use paths::DatabaseRootPath;
 
 pub use super::*;
+pub use db_quota_config::DbQuotaConfig;
 pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
@@ -62,6 +64,11 @@ pub struct ConductorConfig {
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     #[serde(default)]
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// A disk usage quota for the databases of each DNA.
+    /// If omitted, databases grow without bound.
+    #[serde(default)]
+    pub db_quota: Option<DbQuotaConfig>,
     //
     //
     // Which signals to emit
@@ -93,6 +100,7 @@ impl ConductorConfig {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use holochain_types::db::{DbName, QuotaMode};
     use matches::assert_matches;
     use std::path::Path;
     use std::path::PathBuf;
@@ -133,6 +141,7 @@ pub mod tests {
                 keystore: KeystoreConfig::DangerTestKeystore,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
+                db_quota: None,
                 chc_namespace: None,
             }
         );
@@ -183,6 +192,9 @@ pub mod tests {
       network_type: quic_bootstrap
 
     db_sync_strategy: Fast
+    db_quota:
+      limit_bytes: 1000000
+      mode: HardFail
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -224,6 +236,11 @@ pub mod tests {
                 }]),
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
+                db_quota: Some(DbQuotaConfig {
+                    limit_bytes: 1_000_000,
+                    mode: QuotaMode::HardFail,
+                    exempt: vec![DbName::Authored],
+                }),
                 chc_namespace: None,
             }
         );
@@ -251,6 +268,7 @@ pub mod tests {
                 },
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::Fast,
+                db_quota: None,
                 chc_namespace: None,
             }
         );
@@ -0,0 +1,37 @@
+use holochain_types::db::{DbName, DbQuota, QuotaMode};
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A disk usage quota for the databases of each DNA the conductor runs.
+///
+/// Cells of the same DNA share their databases, so each quota covers all of
+/// a DNA's cells together. The quota applies separately to each database of
+/// that DNA, other than the ones that are exempt.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct DbQuotaConfig {
+    /// The number of bytes each database may use.
+    pub limit_bytes: u64,
+    /// What happens to a write that would go over the limit.
+    pub mode: QuotaMode,
+    /// The databases the quota doesn't apply to.
+    /// By default this is only the authored database, so that a cell can
+    /// always write to its own source chain.
+    /// Cache databases evict the data accessed longest ago before the quota
+    /// is enforced on them, so this also bounds the size of each cache.
+    #[serde(default = "default_exempt")]
+    pub exempt: Vec<DbName>,
+}
+
+fn default_exempt() -> Vec<DbName> {
+    vec![DbName::Authored]
+}
+
+impl DbQuotaConfig {
+    /// The quota to set on a database, or `None` if it is exempt.
+    pub fn quota_for(&self, name: DbName) -> Option<DbQuota> {
+        (!self.exempt.contains(&name)).then_some(DbQuota {
+            limit_bytes: self.limit_bytes,
+            mode: self.mode,
+        })
+    }
+}
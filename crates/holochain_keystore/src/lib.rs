@@ -42,4 +42,5 @@ pub use agent_pubkey_ext::*;
 
 pub mod crude_mock_keystore;
 pub mod lair_keystore;
+pub mod mock_keystore;
 pub mod test_keystore;
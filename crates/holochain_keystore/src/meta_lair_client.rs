@@ -12,6 +12,7 @@ const TIME_CHECK_FREQ: std::time::Duration = std::time::Duration::from_secs(5);
 const CON_CHECK_STUB_TAG: &str = "HC_CON_CHK_STUB";
 const RECON_INIT_MS: u64 = 100;
 const RECON_MAX_MS: u64 = 5000;
+const SIGN_BATCH_CHUNK: usize = 32;
 
 type Esnd = tokio::sync::mpsc::UnboundedSender<()>;
 
@@ -191,6 +192,24 @@ impl MetaLairClient {
         }
     }
 
+    /// Generate signatures for a batch of keypair / data pairs.
+    /// Results are returned in the same order as the requests, and a request
+    /// that fails does not fail the rest of the batch.
+    /// Only a fixed number of requests are in flight to lair at once.
+    pub fn sign_batch(
+        &self,
+        requests: Vec<(holo_hash::AgentPubKey, Arc<[u8]>)>,
+    ) -> impl Future<Output = Vec<LairResult<Signature>>> + 'static + Send {
+        use futures::stream::StreamExt;
+        let signs = requests
+            .into_iter()
+            .map(|(pub_key, data)| self.sign(pub_key, data))
+            .collect::<Vec<_>>();
+        futures::stream::iter(signs)
+            .buffered(SIGN_BATCH_CHUNK)
+            .collect()
+    }
+
     /// Construct a new randomized shared secret, associated with given tag
     pub fn new_shared_secret(
         &self,
@@ -397,3 +416,32 @@ impl MetaLairClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_keystore::spawn_test_keystore;
+    use crate::AgentPubKeyExt;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sign_batch_keeps_order_and_fails_per_item() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let agent = keystore.new_sign_keypair_random().await.unwrap();
+        let unknown = holo_hash::AgentPubKey::from_raw_32(vec![0xdb; 32]);
+
+        let mut requests = (0..50u8)
+            .map(|i| (agent.clone(), vec![i; 8].into()))
+            .collect::<Vec<_>>();
+        requests[17].0 = unknown;
+
+        let results = keystore.sign_batch(requests.clone()).await;
+        assert_eq!(results.len(), 50);
+        for (i, ((pub_key, data), result)) in requests.into_iter().zip(results).enumerate() {
+            if i == 17 {
+                assert!(result.is_err());
+            } else {
+                let signature = result.unwrap();
+                assert!(pub_key.verify_signature_raw(&signature, data).await);
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! DANGER! This is a mock keystore for testing, DO NOT USE THIS IN PRODUCTION!
+//!
+//! A [`MockKeystore`] derives its agent keys from one fixed seed, so the keys
+//! it hands out, and the ed25519 signatures made with them, are the same on
+//! every run. Failures and delays can be injected into its requests to
+//! exercise the error paths of the code using it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::FutureExt;
+use kitsune_p2p_types::dependencies::lair_keystore_api::lair_client::traits::AsLairClient;
+use kitsune_p2p_types::dependencies::lair_keystore_api::prelude::*;
+
+use crate::test_keystore::spawn_in_proc_keystore;
+use crate::MetaLairClient;
+
+/// Decides whether a keystore request fails, and with which error.
+type FailureFn = dyn Fn(&LairApiEnum) -> Option<one_err::OneErr> + Send + Sync + 'static;
+
+/// Spawn a mock keystore whose agent keys are derived from `seed`.
+pub async fn spawn_mock_keystore(seed: [u8; 32]) -> LairResult<MockKeystore> {
+    let keystore = spawn_in_proc_keystore().await?;
+    let inner = Arc::new(MockKeystoreInner {
+        seed,
+        store: keystore.store().await?,
+        agents: tokio::sync::Mutex::new(HashMap::new()),
+        failure: parking_lot::Mutex::new(None),
+        delay: parking_lot::Mutex::new(None),
+    });
+    let client = MockLairClient {
+        real: keystore.new_client().await?,
+        inner: inner.clone(),
+    };
+
+    let (s, _) = tokio::sync::mpsc::unbounded_channel();
+    Ok(MockKeystore {
+        keystore: MetaLairClient(
+            Arc::new(parking_lot::Mutex::new(LairClient(Arc::new(client)))),
+            s,
+        ),
+        inner,
+    })
+}
+
+/// A keystore whose agent keys are derived from a fixed seed.
+///
+/// Only the keys of [`MockKeystore::agent`] are derived from the seed. Keys
+/// made with `AgentPubKey::new_random` are random as usual.
+#[derive(Clone)]
+pub struct MockKeystore {
+    keystore: MetaLairClient,
+    inner: Arc<MockKeystoreInner>,
+}
+
+struct MockKeystoreInner {
+    seed: [u8; 32],
+    store: LairStore,
+    agents: tokio::sync::Mutex<HashMap<u32, holo_hash::AgentPubKey>>,
+    failure: parking_lot::Mutex<Option<Arc<FailureFn>>>,
+    delay: parking_lot::Mutex<Option<Duration>>,
+}
+
+impl MockKeystore {
+    /// The keystore client to hand to the code under test.
+    pub fn keystore(&self) -> MetaLairClient {
+        self.keystore.clone()
+    }
+
+    /// The agent key at `index`, which is the same for every keystore
+    /// spawned with the same seed.
+    pub async fn agent(&self, index: u32) -> LairResult<holo_hash::AgentPubKey> {
+        let mut agents = self.inner.agents.lock().await;
+        if let Some(agent) = agents.get(&index) {
+            return Ok(agent.clone());
+        }
+        let mut message = self.inner.seed.to_vec();
+        message.extend_from_slice(&index.to_le_bytes());
+        let seed = sodoken::BufWriteSized::<32>::new_no_lock();
+        sodoken::hash::blake2b::hash(seed.clone(), sodoken::BufRead::new_no_lock(&message)).await?;
+        let tag = format!("mock-agent-{}", index);
+        let seed_info = self
+            .inner
+            .store
+            .insert_seed(seed.to_read_sized(), tag.into(), false)
+            .await?;
+        let agent = holo_hash::AgentPubKey::from_raw_32(seed_info.ed25519_pub_key.0.to_vec());
+        agents.insert(index, agent.clone());
+        Ok(agent)
+    }
+
+    /// Fail every request for which `failure` returns an error, with that
+    /// error. Replaces any failure injected before.
+    pub fn fail_with<F>(&self, failure: F)
+    where
+        F: Fn(&LairApiEnum) -> Option<one_err::OneErr> + Send + Sync + 'static,
+    {
+        *self.inner.failure.lock() = Some(Arc::new(failure));
+    }
+
+    /// Fail every signing request with `error`.
+    pub fn fail_signing(&self, error: &str) {
+        let error = error.to_string();
+        self.fail_with(move |request| match request {
+            LairApiEnum::ReqSignByPubKey(_) => Some(one_err::OneErr::new(error.clone())),
+            _ => None,
+        });
+    }
+
+    /// Stop failing requests.
+    pub fn clear_failure(&self) {
+        *self.inner.failure.lock() = None;
+    }
+
+    /// Delay every request by `delay`, or stop delaying them with `None`.
+    pub fn set_delay(&self, delay: Option<Duration>) {
+        *self.inner.delay.lock() = delay;
+    }
+}
+
+/// Passes requests on to a real in-memory keystore, unless they are to fail.
+struct MockLairClient {
+    real: LairClient,
+    inner: Arc<MockKeystoreInner>,
+}
+
+impl AsLairClient for MockLairClient {
+    fn get_enc_ctx_key(&self) -> sodoken::BufReadSized<32> {
+        self.real.0.get_enc_ctx_key()
+    }
+
+    fn get_dec_ctx_key(&self) -> sodoken::BufReadSized<32> {
+        self.real.0.get_dec_ctx_key()
+    }
+
+    fn shutdown(&self) -> futures::future::BoxFuture<'static, LairResult<()>> {
+        self.real.0.shutdown()
+    }
+
+    fn request(
+        &self,
+        request: LairApiEnum,
+    ) -> futures::future::BoxFuture<'static, LairResult<LairApiEnum>> {
+        let failure = self
+            .inner
+            .failure
+            .lock()
+            .as_ref()
+            .and_then(|failure| failure(&request));
+        let delay = *self.inner.delay.lock();
+        let real = self.real.clone();
+        async move {
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+            if let Some(error) = failure {
+                return Err(error);
+            }
+            real.0.request(request).await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_pubkey_ext::AgentPubKeyExt;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn keystores_with_the_same_seed_have_the_same_keys_and_signatures() {
+        let data: Arc<[u8]> = b"signature test data".to_vec().into();
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let mock = spawn_mock_keystore([1; 32]).await.unwrap();
+            let agent = mock.agent(3).await.unwrap();
+            assert_eq!(mock.agent(3).await.unwrap(), agent);
+            assert_ne!(mock.agent(4).await.unwrap(), agent);
+            let signature = agent
+                .sign_raw(&mock.keystore(), data.clone())
+                .await
+                .unwrap();
+            runs.push((agent, signature));
+        }
+        assert_eq!(runs[0], runs[1]);
+
+        let other = spawn_mock_keystore([2; 32]).await.unwrap();
+        assert_ne!(other.agent(3).await.unwrap(), runs[0].0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn injected_failures_fail_matching_requests_until_cleared() {
+        let mock = spawn_mock_keystore([1; 32]).await.unwrap();
+        let agent = mock.agent(0).await.unwrap();
+        let data: Arc<[u8]> = b"signature test data".to_vec().into();
+
+        mock.fail_signing("test error");
+        assert_eq!(
+            agent.sign_raw(&mock.keystore(), data.clone()).await,
+            Err(one_err::OneErr::new("test error"))
+        );
+        // Other requests still go through.
+        holo_hash::AgentPubKey::new_random(&mock.keystore())
+            .await
+            .unwrap();
+
+        mock.clear_failure();
+        agent.sign_raw(&mock.keystore(), data).await.unwrap();
+    }
+}
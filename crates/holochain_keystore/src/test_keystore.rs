@@ -34,26 +34,7 @@ fn s(s: &str) -> [u8; 32] {
 
 /// Construct a new TestKeystore with the new lair api.
 pub async fn spawn_test_keystore() -> LairResult<MetaLairClient> {
-    // in-memory secure random passphrase
-    let passphrase = sodoken::BufWrite::new_mem_locked(32)?;
-    sodoken::random::bytes_buf(passphrase.clone()).await?;
-
-    // in-mem / in-proc config
-    let config = Arc::new(
-        PwHashLimits::Minimum
-            .with_exec(|| {
-                lair_keystore_api::config::LairServerConfigInner::new("/", passphrase.to_read())
-            })
-            .await?,
-    );
-
-    // the keystore
-    let keystore = lair_keystore_api::in_proc_keystore::InProcKeystore::new(
-        config,
-        lair_keystore_api::mem_store::create_mem_store_factory(),
-        passphrase.to_read(),
-    )
-    .await?;
+    let keystore = spawn_in_proc_keystore().await?;
 
     // get the store and inject test seeds
     let store = keystore.store().await?;
@@ -75,3 +56,46 @@ pub async fn spawn_test_keystore() -> LairResult<MetaLairClient> {
     let (s, _) = tokio::sync::mpsc::unbounded_channel();
     Ok(MetaLairClient(Arc::new(parking_lot::Mutex::new(client)), s))
 }
+
+/// Spawn an empty in-memory lair keystore.
+pub(crate) async fn spawn_in_proc_keystore(
+) -> LairResult<lair_keystore_api::in_proc_keystore::InProcKeystore> {
+    // in-memory secure random passphrase
+    let passphrase = sodoken::BufWrite::new_mem_locked(32)?;
+    sodoken::random::bytes_buf(passphrase.clone()).await?;
+
+    // in-mem / in-proc config
+    let config = Arc::new(
+        PwHashLimits::Minimum
+            .with_exec(|| {
+                lair_keystore_api::config::LairServerConfigInner::new("/", passphrase.to_read())
+            })
+            .await?,
+    );
+
+    // the keystore
+    lair_keystore_api::in_proc_keystore::InProcKeystore::new(
+        config,
+        lair_keystore_api::mem_store::create_mem_store_factory(),
+        passphrase.to_read(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_agent_signatures_are_reproducible() {
+        let agent = holo_hash::AgentPubKey::try_from(TEST_AGENT_PK_1).unwrap();
+        let data: Arc<[u8]> = b"signature test data".to_vec().into();
+
+        let mut signatures = Vec::new();
+        for _ in 0..2 {
+            let keystore = spawn_test_keystore().await.unwrap();
+            signatures.push(keystore.sign(agent.clone(), data.clone()).await.unwrap());
+        }
+        assert_eq!(signatures[0], signatures[1]);
+    }
+}
@@ -43,6 +43,7 @@ pub trait HolochainP2pDnaT {
     async fn leave(&self, agent: AgentPubKey) -> actor::HolochainP2pResult<()>;
 
     /// Invoke a zome function on a remote node (if you have been granted the capability).
+    /// The remote node runs the call under the given `call_id`.
     #[allow(clippy::too_many_arguments)]
     async fn call_remote(
         &self,
@@ -55,6 +56,7 @@ pub trait HolochainP2pDnaT {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: u64,
     ) -> actor::HolochainP2pResult<SerializedBytes>;
 
     /// Invoke a zome function on a remote node (if you have been granted the capability).
@@ -204,6 +206,7 @@ impl HolochainP2pDnaT for HolochainP2pDna {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: u64,
     ) -> actor::HolochainP2pResult<SerializedBytes> {
         self.sender
             .call_remote(
@@ -217,6 +220,7 @@ impl HolochainP2pDnaT for HolochainP2pDna {
                 payload,
                 nonce,
                 expires_at,
+                call_id,
             )
             .await
     }
@@ -135,12 +135,13 @@ impl WrapEvtSender {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: Option<u64>,
     ) -> impl Future<Output = HolochainP2pResult<SerializedBytes>> + 'static + Send {
         timing_trace!(
             {
                 self.0.call_remote(
                     dna_hash, from, signature, to_agent, zome_name, fn_name, cap_secret, payload,
-                    nonce, expires_at,
+                    nonce, expires_at, call_id,
                 )
             },
             "(hp2p:handle) call_remote",
@@ -372,6 +373,7 @@ impl HolochainP2pActor {
         data: Vec<u8>,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: Option<u64>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
@@ -387,6 +389,7 @@ impl HolochainP2pActor {
                     ExternIO::from(data),
                     nonce,
                     expires_at,
+                    call_id,
                 )
                 .await;
             res.map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -692,7 +695,31 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 expires_at,
             } => self.handle_incoming_call_remote(
                 space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret, data,
-                *nonce, expires_at,
+                *nonce, expires_at, None,
+            ),
+            crate::wire::WireMessage::CallRemoteV2 {
+                zome_name,
+                fn_name,
+                cap_secret,
+                data,
+                from_agent,
+                signature,
+                to_agent,
+                nonce,
+                expires_at,
+                call_id,
+            } => self.handle_incoming_call_remote(
+                space,
+                from_agent,
+                signature,
+                to_agent,
+                zome_name,
+                fn_name,
+                cap_secret,
+                data,
+                *nonce,
+                expires_at,
+                Some(call_id),
             ),
             crate::wire::WireMessage::CallRemoteMulti {
                 zome_name,
@@ -710,7 +737,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 {
                     Some((signature, to_agent)) => self.handle_incoming_call_remote(
                         space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret,
-                        data, *nonce, expires_at,
+                        data, *nonce, expires_at, None,
                     ),
                     None => Err(HolochainP2pError::RoutingAgentError(to_agent).into()),
                 }
@@ -786,7 +813,39 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             } => {
                 let fut = self.handle_incoming_call_remote(
                     space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret, data,
-                    *nonce, expires_at,
+                    *nonce, expires_at, None,
+                );
+                Ok(async move {
+                    let _ = fut?.await?;
+                    Ok(())
+                }
+                .boxed()
+                .into())
+            }
+            crate::wire::WireMessage::CallRemoteV2 {
+                zome_name,
+                fn_name,
+                from_agent,
+                signature,
+                to_agent,
+                cap_secret,
+                data,
+                nonce,
+                expires_at,
+                call_id,
+            } => {
+                let fut = self.handle_incoming_call_remote(
+                    space,
+                    from_agent,
+                    signature,
+                    to_agent,
+                    zome_name,
+                    fn_name,
+                    cap_secret,
+                    data,
+                    *nonce,
+                    expires_at,
+                    Some(call_id),
                 );
                 Ok(async move {
                     let _ = fut?.await?;
@@ -812,7 +871,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                     Some((signature, to_agent)) => {
                         let fut = self.handle_incoming_call_remote(
                             space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret,
-                            data, *nonce, expires_at,
+                            data, *nonce, expires_at, None,
                         );
                         Ok(async move {
                             let _ = fut?.await?;
@@ -988,13 +1047,14 @@ impl HolochainP2pHandler for HolochainP2pActor {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: u64,
     ) -> HolochainP2pHandlerResult<SerializedBytes> {
         let space = dna_hash.into_kitsune();
         let to_agent_kitsune = to_agent.clone().into_kitsune();
 
         let req = crate::wire::WireMessage::call_remote(
             zome_name, fn_name, from_agent, signature, to_agent, cap_secret, payload, nonce,
-            expires_at,
+            expires_at, call_id,
         )
         .encode()?;
 
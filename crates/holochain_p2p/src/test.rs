@@ -42,6 +42,7 @@ impl HolochainP2pHandler for StubNetwork {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: u64,
     ) -> HolochainP2pHandlerResult<SerializedBytes> {
         Err("stub".into())
     }
@@ -252,8 +253,10 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_call_remote_workflow() {
-        let (dna, a1, a2, _) = test_setup();
-        let keystore = test_keystore();
+        let (dna, _, a2, _) = test_setup();
+        let mock = test_keystore_deterministic([0; 32]);
+        let keystore = mock.keystore();
+        let a1 = mock.agent(0).await.unwrap();
 
         let (p2p, mut evt) = spawn_holochain_p2p(
             KitsuneP2pConfig::default(),
@@ -268,7 +271,10 @@ mod tests {
             while let Some(evt) = evt.next().await {
                 use crate::types::event::HolochainP2pEvent::*;
                 match evt {
-                    CallRemote { respond, .. } => {
+                    CallRemote {
+                        respond, call_id, ..
+                    } => {
+                        assert_eq!(Some(42), call_id);
                         respond.r(Ok(
                             async move { Ok(UnsafeBytes::from(b"yada".to_vec()).into()) }
                                 .boxed()
@@ -321,7 +327,7 @@ mod tests {
 
         let res = p2p
             .call_remote(
-                dna, a1, signature, a2, zome_name, fn_name, None, payload, nonce, expires_at,
+                dna, a1, signature, a2, zome_name, fn_name, None, payload, nonce, expires_at, 42,
             )
             .await
             .unwrap();
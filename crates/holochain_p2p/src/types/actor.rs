@@ -263,6 +263,7 @@ ghost_actor::ghost_chan! {
             payload: ExternIO,
             nonce: Nonce256Bits,
             expires_at: Timestamp,
+            call_id: u64,
         ) -> SerializedBytes;
 
         /// Invoke a zome function on a remote node (if you have been granted the capability).
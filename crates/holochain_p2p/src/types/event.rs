@@ -174,6 +174,7 @@ ghost_actor::ghost_chan! {
         fn query_peer_density(dna_hash: DnaHash, kitsune_space: Arc<kitsune_p2p::KitsuneSpace>, dht_arc: kitsune_p2p_types::dht_arc::DhtArc) -> kitsune_p2p_types::dht::PeerView;
 
         /// A remote node is attempting to make a remote call on us.
+        /// `call_id` is the caller's id for the call, if it sent one.
         fn call_remote(
             dna_hash: DnaHash,
             from_agent: AgentPubKey,
@@ -185,6 +186,7 @@ ghost_actor::ghost_chan! {
             payload: ExternIO,
             nonce: Nonce256Bits,
             expires_at: Timestamp,
+            call_id: Option<u64>,
         ) -> SerializedBytes;
 
         /// A remote node is publishing data in a range we claim to be holding.
@@ -339,6 +339,7 @@ impl HolochainP2pMockMsg {
         match self {
             HolochainP2pMockMsg::Wire { msg, .. } => match &msg {
                 crate::wire::WireMessage::CallRemote { .. }
+                | crate::wire::WireMessage::CallRemoteV2 { .. }
                 | crate::wire::WireMessage::CallRemoteMulti { .. }
                 | crate::wire::WireMessage::ValidationReceipt { .. }
                 | crate::wire::WireMessage::Get { .. }
@@ -368,6 +369,7 @@ impl HolochainP2pMockMsg {
             } => {
                 let call = match &msg {
                     crate::wire::WireMessage::CallRemote { .. }
+                    | crate::wire::WireMessage::CallRemoteV2 { .. }
                     | crate::wire::WireMessage::CallRemoteMulti { .. }
                     | crate::wire::WireMessage::ValidationReceipt { .. }
                     | crate::wire::WireMessage::Get { .. }
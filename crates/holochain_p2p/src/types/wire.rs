@@ -25,6 +25,8 @@ impl WireDhtOpData {
 #[serde(tag = "type", content = "content")]
 #[allow(missing_docs)]
 pub enum WireMessage {
+    /// A remote call from a node that predates [`WireMessage::CallRemoteV2`].
+    /// It is still accepted, and the callee gives the call an id of its own.
     CallRemote {
         zome_name: ZomeName,
         fn_name: FunctionName,
@@ -37,6 +39,21 @@ pub enum WireMessage {
         nonce: Box<Nonce256Bits>,
         expires_at: Timestamp,
     },
+    /// A remote call carrying the caller's call id, so that the logs of both
+    /// nodes record the call under the same id.
+    CallRemoteV2 {
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        from_agent: holo_hash::AgentPubKey,
+        signature: Signature,
+        to_agent: AgentPubKey,
+        cap_secret: Option<CapSecret>,
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        nonce: Box<Nonce256Bits>,
+        expires_at: Timestamp,
+        call_id: u64,
+    },
     CallRemoteMulti {
         zome_name: ZomeName,
         fn_name: FunctionName,
@@ -108,8 +125,9 @@ impl WireMessage {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        call_id: u64,
     ) -> WireMessage {
-        Self::CallRemote {
+        Self::CallRemoteV2 {
             zome_name,
             fn_name,
             from_agent,
@@ -119,6 +137,7 @@ impl WireMessage {
             data: payload.into_vec(),
             nonce: Box::new(nonce),
             expires_at,
+            call_id,
         }
     }
 
@@ -191,3 +210,89 @@ impl WireMessage {
         Self::CountersigningSessionNegotiation { message }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::prelude::AgentPubKeyExt;
+    use holochain_zome_types::zome_io::ZomeCallUnsigned;
+
+    /// A remote call whose every field, including the signature made with the
+    /// deterministic test keystore, is the same on every run.
+    async fn fixture_call_remote() -> WireMessage {
+        let mock = holochain_types::test_utils::test_keystore_deterministic([7; 32]);
+        let from_agent = mock.agent(0).await.unwrap();
+        let to_agent = mock.agent(1).await.unwrap();
+        let zome_call = ZomeCallUnsigned {
+            provenance: from_agent.clone(),
+            cell_id: CellId::new(DnaHash::from_raw_32(vec![1; 32]), to_agent.clone()),
+            zome_name: "zome".into(),
+            fn_name: "fn".into(),
+            cap_secret: None,
+            payload: ExternIO::encode("payload").unwrap(),
+            nonce: Nonce256Bits::from([0; 32]),
+            expires_at: Timestamp(0),
+        };
+        let signature = from_agent
+            .sign_raw(&mock.keystore(), zome_call.data_to_sign().unwrap())
+            .await
+            .unwrap();
+        WireMessage::call_remote(
+            zome_call.zome_name,
+            zome_call.fn_name,
+            from_agent,
+            signature,
+            to_agent,
+            zome_call.cap_secret,
+            zome_call.payload,
+            zome_call.nonce,
+            zome_call.expires_at,
+            42,
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn call_remote_matches_its_wire_fixture() {
+        let fixture = include_bytes!("fixtures/call_remote_v2.bin");
+        let encoded = fixture_call_remote().await.encode().unwrap();
+        assert_eq!(&encoded[..], &fixture[..]);
+
+        let decoded = WireMessage::decode(fixture).unwrap();
+        assert_eq!(decoded.encode().unwrap(), encoded);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn call_remote_from_an_older_node_matches_its_wire_fixture() {
+        let fixture = include_bytes!("fixtures/call_remote_v1.bin");
+        let v1 = match fixture_call_remote().await {
+            WireMessage::CallRemoteV2 {
+                zome_name,
+                fn_name,
+                from_agent,
+                signature,
+                to_agent,
+                cap_secret,
+                data,
+                nonce,
+                expires_at,
+                ..
+            } => WireMessage::CallRemote {
+                zome_name,
+                fn_name,
+                from_agent,
+                signature,
+                to_agent,
+                cap_secret,
+                data,
+                nonce,
+                expires_at,
+            },
+            other => panic!("unexpected message {:?}", other),
+        };
+        let encoded = v1.encode().unwrap();
+        assert_eq!(&encoded[..], &fixture[..]);
+
+        let decoded = WireMessage::decode(fixture).unwrap();
+        assert!(matches!(decoded, WireMessage::CallRemote { .. }));
+    }
+}
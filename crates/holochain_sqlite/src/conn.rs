@@ -7,9 +7,10 @@ use std::{
     any::Any,
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Weak},
     time::Duration,
 };
+use tokio::sync::Semaphore;
 
 const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -43,6 +44,29 @@ pub(crate) static DATABASE_HANDLES: Lazy<Databases> = Lazy::new(|| {
     Databases::new()
 });
 
+/// The write permit of each database file, shared by every handle on it.
+/// A handle opened after [`DbWrite::close`] while clones of the closed one
+/// are still alive then waits for their writes instead of running alongside.
+static WRITE_SEMAPHORES: Lazy<parking_lot::Mutex<HashMap<PathBuf, Weak<Semaphore>>>> =
+    Lazy::new(Default::default);
+
+/// The write permit for the database at `path`, which in-memory databases
+/// don't share.
+pub(crate) fn write_semaphore_for(path: Option<&Path>) -> Arc<Semaphore> {
+    let path = match path {
+        Some(path) => path,
+        None => return Arc::new(Semaphore::new(1)),
+    };
+    let mut semaphores = WRITE_SEMAPHORES.lock();
+    semaphores.retain(|_, semaphore| semaphore.strong_count() > 0);
+    if let Some(semaphore) = semaphores.get(path).and_then(Weak::upgrade) {
+        return semaphore;
+    }
+    let semaphore = Arc::new(Semaphore::new(1));
+    semaphores.insert(path.to_owned(), Arc::downgrade(&semaphore));
+    semaphore
+}
+
 static R2D2_THREADPOOL: Lazy<Arc<ScheduledThreadPool>> = Lazy::new(|| {
     let t = ScheduledThreadPool::new(1);
     Arc::new(t)
@@ -101,17 +125,32 @@ impl Databases {
             },
         }
     }
+
+    /// Remove a database from the map if it exists.
+    /// Returns true if a database was removed.
+    pub fn remove(&self, path: &Path) -> bool {
+        self.dbs.write().remove(path).is_some()
+    }
 }
 
 pub(crate) fn new_connection_pool(
     path: Option<&Path>,
     synchronous_level: DbSyncLevel,
+) -> ConnectionPool {
+    new_connection_pool_with_flags(path, synchronous_level, OpenFlags::default())
+}
+
+pub(crate) fn new_connection_pool_with_flags(
+    path: Option<&Path>,
+    synchronous_level: DbSyncLevel,
+    flags: OpenFlags,
 ) -> ConnectionPool {
     use r2d2_sqlite::SqliteConnectionManager;
     let manager = match path {
         Some(path) => SqliteConnectionManager::file(path),
         None => SqliteConnectionManager::memory(),
-    };
+    }
+    .with_flags(flags);
     let customizer = Box::new(ConnCustomizer { synchronous_level });
     // We need the same amount of connections as reader threads plus one for the writer thread.
     let max_cons = num_read_threads() + 1;
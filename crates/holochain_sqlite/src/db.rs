@@ -1,18 +1,20 @@
 //! Functions dealing with obtaining and referencing singleton databases
 
 use crate::{
-    conn::{new_connection_pool, ConnectionPool, DbSyncLevel, PConn, DATABASE_HANDLES},
+    conn::{
+        new_connection_pool, new_connection_pool_with_flags, write_semaphore_for, ConnectionPool,
+        DbSyncLevel, PConn, DATABASE_HANDLES,
+    },
     prelude::*,
 };
 use derive_more::Into;
 use futures::Future;
 use holo_hash::DnaHash;
 use kitsune_p2p::KitsuneSpace;
-use parking_lot::Mutex;
 use rusqlite::*;
 use shrinkwraprs::Shrinkwrap;
+use std::path::Path;
 use std::sync::Arc;
-use std::{collections::HashMap, path::Path};
 use std::{path::PathBuf, sync::atomic::AtomicUsize};
 use tokio::{
     sync::{OwnedSemaphorePermit, Semaphore},
@@ -25,6 +27,19 @@ pub use p2p_agent_store::*;
 mod p2p_metrics;
 pub use p2p_metrics::*;
 
+mod metrics;
+use metrics::DbMetricsCounters;
+pub use metrics::{DbMetrics, StateMetricsSink};
+
+mod quota;
+pub use quota::{DbName, DbQuota, QuotaEviction, QuotaMode};
+
+mod stats;
+pub use stats::*;
+
+#[cfg(test)]
+mod tests;
+
 #[async_trait::async_trait]
 /// A trait for being generic over [`DbWrite`] and [`DbRead`] that
 /// both implement read access.
@@ -106,6 +121,8 @@ pub struct DbRead<Kind: DbKindT> {
     read_semaphore: Arc<Semaphore>,
     max_readers: usize,
     num_readers: Arc<AtomicUsize>,
+    metrics: Arc<DbMetricsCounters>,
+    quota: Arc<parking_lot::RwLock<quota::QuotaState>>,
 }
 
 #[derive(Shrinkwrap)]
@@ -131,6 +148,39 @@ impl<Kind: DbKindT> PermittedConn for DbWrite<Kind> {
 }
 
 impl<Kind: DbKindT> DbRead<Kind> {
+    /// Open an existing database for reading only, without creating,
+    /// initializing or migrating it.
+    /// The handle is not shared through the static handle cache, and it can
+    /// be opened while another handle or process is writing to the same file.
+    /// Any attempt to write through its connections fails with `SQLITE_READONLY`.
+    pub fn open_read_only(path_prefix: &Path, kind: Kind) -> DatabaseResult<Self> {
+        let path = path_prefix.join(kind.filename());
+        if !path.is_file() {
+            return Err(DatabaseError::DatabaseMissing(path));
+        }
+        let pool = new_connection_pool_with_flags(
+            Some(&path),
+            DbSyncLevel::default(),
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        );
+        // Check the file can actually be read as a database.
+        pool.get()?
+            .query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))?;
+        Ok(DbRead {
+            write_semaphore: Arc::new(Semaphore::new(1)),
+            read_semaphore: Arc::new(Semaphore::new(num_read_threads())),
+            max_readers: num_read_threads(),
+            num_readers: Arc::new(AtomicUsize::new(0)),
+            metrics: Default::default(),
+            quota: Default::default(),
+            kind,
+            path,
+            connection_pool: pool,
+        })
+    }
+
     pub fn conn(&self) -> DatabaseResult<PConn> {
         self.connection_pooled()
     }
@@ -184,12 +234,28 @@ impl<Kind: DbKindT> DbRead<Kind> {
         self.num_readers
             .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
         let mut conn = self.conn()?;
+        let start = std::time::Instant::now();
         let r = tokio::task::spawn_blocking(move || conn.with_reader(f))
             .await
             .map_err(DatabaseError::from)?;
+        self.metrics.record_read(self.kind(), start.elapsed());
         r
     }
 
+    /// Write a standalone copy of this database to a new file at `path`.
+    /// The copy is made from a single read snapshot, so it is consistent
+    /// even while other connections are writing.
+    pub async fn backup_to(&self, path: &Path) -> DatabaseResult<()> {
+        let _g = self.acquire_reader_permit().await;
+        let conn = self.conn()?;
+        let path = path.to_string_lossy().into_owned();
+        task::spawn_blocking(move || {
+            conn.execute("VACUUM main INTO ?", [path])?;
+            DatabaseResult::Ok(())
+        })
+        .await?
+    }
+
     async fn acquire_reader_permit(&self) -> OwnedSemaphorePermit {
         self.read_semaphore
             .clone()
@@ -226,6 +292,40 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         })
     }
 
+    /// Open a database of this kind under `path_prefix` from a copy made
+    /// with [`DbRead::backup_to`].
+    /// Refuses to overwrite a database that already exists at the destination,
+    /// and refuses a backup that lacks any of the tables of this kind.
+    pub fn restore_from(backup: &Path, path_prefix: &Path, kind: Kind) -> DatabaseResult<Self> {
+        let path = path_prefix.join(kind.filename());
+        if path.exists() {
+            return Err(DatabaseError::DatabaseAlreadyExists(path));
+        }
+        let missing = missing_tables(backup, kind.kind())?;
+        if !missing.is_empty() {
+            return Err(DatabaseError::BackupMissingTables {
+                db_kind: kind.kind().to_string(),
+                missing,
+            });
+        }
+        let parent = path
+            .parent()
+            .ok_or_else(|| DatabaseError::DatabaseMissing(path_prefix.to_owned()))?;
+        prepare_db_dir(parent)?;
+        std::fs::copy(backup, &path)?;
+        Self::open(path_prefix, kind)
+    }
+
+    /// Remove this database from the static handle cache so the next
+    /// [`DbWrite::open`] at the same path initializes a fresh handle
+    /// instead of returning this one.
+    /// Any other clones of this handle keep working until they are dropped.
+    /// Until then they share their write permit with the reopened handle,
+    /// so there is still only one writer on the file.
+    pub fn close(self) {
+        DATABASE_HANDLES.remove(self.path());
+    }
+
     pub(crate) fn new(
         path_prefix: Option<&Path>,
         kind: Kind,
@@ -237,10 +337,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                 let parent = path
                     .parent()
                     .ok_or_else(|| DatabaseError::DatabaseMissing(path_prefix.to_owned()))?;
-                if !parent.is_dir() {
-                    std::fs::create_dir_all(parent)
-                        .map_err(|_e| DatabaseError::DatabaseMissing(parent.to_owned()))?;
-                }
+                prepare_db_dir(parent)?;
                 // Check if the database is valid and take the appropriate
                 // action if it isn't.
                 match Connection::open(&path)
@@ -290,34 +387,21 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         crate::table::initialize_database(&mut conn, kind.kind())?;
 
         Ok(DbWrite(DbRead {
-            write_semaphore: Self::get_write_semaphore(kind.kind()),
-            read_semaphore: Self::get_read_semaphore(kind.kind()),
+            // The permits belong to this database file rather than being
+            // shared by every database of the same kind, so that separate
+            // databases (e.g. multiple conductors in one process) don't contend.
+            write_semaphore: write_semaphore_for(path.as_deref()),
+            read_semaphore: Arc::new(Semaphore::new(num_read_threads())),
             max_readers: num_read_threads(),
             num_readers: Arc::new(AtomicUsize::new(0)),
+            metrics: Default::default(),
+            quota: Default::default(),
             kind,
             path: path.unwrap_or_default(),
             connection_pool: pool,
         }))
     }
 
-    fn get_write_semaphore(kind: DbKind) -> Arc<Semaphore> {
-        static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
-            once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
-        MAP.lock()
-            .entry(kind)
-            .or_insert_with(|| Arc::new(Semaphore::new(1)))
-            .clone()
-    }
-
-    fn get_read_semaphore(kind: DbKind) -> Arc<Semaphore> {
-        static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
-            once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
-        MAP.lock()
-            .entry(kind)
-            .or_insert_with(|| Arc::new(Semaphore::new(num_read_threads())))
-            .clone()
-    }
-
     /// Create a unique db in a temp dir with no static management of the
     /// connection pool, useful for testing.
     #[cfg(any(test, feature = "test_utils"))]
@@ -338,10 +422,20 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     {
         let _g = self.acquire_writer_permit().await;
         let mut conn = self.conn()?;
-        let r = task::spawn_blocking(move || conn.with_commit_sync(f))
-            .await
-            .map_err(DatabaseError::from)?;
-        r
+        let start = std::time::Instant::now();
+        let (quota, kind, metrics) = (self.quota_state(), self.kind().kind(), self.metrics.clone());
+        let r = task::spawn_blocking(move || {
+            conn.with_commit_sync(|txn| commit_within_quota(txn, f, &quota, &kind, &metrics))
+        })
+        .await
+        .map_err(DatabaseError::from)?;
+        self.record_commit(start, r)
+    }
+
+    /// Delete every row of every table in this database within a single
+    /// transaction, leaving the schema in place.
+    pub async fn clear_all(&self) -> DatabaseResult<()> {
+        self.async_commit(|txn| clear_all_tables(txn)).await
     }
 
     #[cfg(any(test, feature = "test_utils"))]
@@ -363,7 +457,23 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     {
         let _g = self.acquire_writer_permit().await;
         let mut conn = self.conn()?;
-        task::block_in_place(move || conn.with_commit_sync(f))
+        let start = std::time::Instant::now();
+        let (quota, kind) = (self.quota_state(), self.kind().kind());
+        let r = task::block_in_place(move || {
+            conn.with_commit_sync(|txn| commit_within_quota(txn, f, &quota, &kind, &self.metrics))
+        });
+        self.record_commit(start, r)
+    }
+
+    fn record_commit<E, R>(
+        &self,
+        start: std::time::Instant,
+        r: Result<(R, u64), E>,
+    ) -> Result<R, E> {
+        let bytes = r.as_ref().map_or(0, |(_, bytes)| *bytes);
+        self.metrics
+            .record_write(self.kind(), start.elapsed(), bytes);
+        r.map(|(r, _)| r)
     }
 
     async fn acquire_writer_permit(&self) -> OwnedSemaphorePermit {
@@ -376,6 +486,146 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     }
 }
 
+/// Run `f` in a write transaction and check the quota before it is committed.
+/// Also returns how much the transaction grew the bytes of pages in use by,
+/// which is only measured if there is a metrics sink to report it to.
+fn commit_within_quota<E, R, F>(
+    txn: &mut Transaction,
+    f: F,
+    quota: &quota::QuotaState,
+    kind: &DbKind,
+    metrics: &DbMetricsCounters,
+) -> Result<(R, u64), E>
+where
+    E: From<DatabaseError>,
+    F: FnOnce(&mut Transaction) -> Result<R, E>,
+{
+    let measure = metrics.has_sink();
+    let before = quota.usage_before(txn, measure)?;
+    let r = f(txn)?;
+    quota.enforce(txn, before, kind, metrics)?;
+    let bytes = match before {
+        Some(before) if measure => quota::used_bytes(txn)?.saturating_sub(before),
+        _ => 0,
+    };
+    Ok((r, bytes))
+}
+
+/// The tables a fresh database of this kind has that the database at `path` lacks.
+fn missing_tables(path: &Path, db_kind: DbKind) -> DatabaseResult<Vec<String>> {
+    fn tables(conn: &Connection) -> DatabaseResult<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        )?;
+        let tables = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(tables)
+    }
+    let mut expected = Connection::open_in_memory()?;
+    crate::table::initialize_database(&mut expected, db_kind)?;
+    let mut found = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    crate::conn::initialize_connection(&mut found, DbSyncLevel::default())?;
+    let found = tables(&found)?;
+    let mut missing: Vec<String> = tables(&expected)?
+        .into_iter()
+        .filter(|table| !found.contains(table))
+        .collect();
+    missing.sort();
+    Ok(missing)
+}
+
+/// Make sure the directory a database file goes in exists and can be
+/// written to, creating it if needed. Every directory created is made
+/// accessible only by the current user.
+fn prepare_db_dir(dir: &Path) -> DatabaseResult<()> {
+    let dir_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => DatabaseError::PermissionDenied(dir.to_owned()),
+        _ => DatabaseError::DatabaseMissing(dir.to_owned()),
+    };
+    if !dir.is_dir() {
+        // A file anywhere along the path would make creation fail with an
+        // unhelpful error, so find it first.
+        if let Some(file) = dir.ancestors().find(|a| a.exists() && !a.is_dir()) {
+            return Err(DatabaseError::NotADirectory(file.to_owned()));
+        }
+        // Create the missing directories one at a time, outermost first,
+        // so the parents are private as well as the database directory.
+        let missing: Vec<&Path> = dir
+            .ancestors()
+            .take_while(|a| !a.as_os_str().is_empty() && !a.exists())
+            .collect();
+        for missing_dir in missing.into_iter().rev() {
+            match std::fs::create_dir(missing_dir) {
+                Ok(()) => (),
+                // Another handle may have created it in the meantime.
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && missing_dir.is_dir() => {
+                    continue
+                }
+                Err(e) => return Err(dir_error(e)),
+            }
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(missing_dir, std::fs::Permissions::from_mode(0o700))
+                    .map_err(dir_error)?;
+            }
+        }
+    }
+    #[cfg(unix)]
+    warn_if_parent_is_shared(dir);
+
+    // Opening a database only fails on the first write, so check that the
+    // directory can be written to now with a probe file.
+    let probe = dir.join(".write-probe");
+    std::fs::write(&probe, b"").map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => DatabaseError::PermissionDenied(dir.to_owned()),
+        _ => DatabaseError::from(e),
+    })?;
+    std::fs::remove_file(&probe)?;
+    Ok(())
+}
+
+/// Others who can write to the parent of a database directory can replace
+/// the directory, so warn about it. Directories with the sticky bit set,
+/// like `/tmp`, only let others remove their own entries.
+#[cfg(unix)]
+fn warn_if_parent_is_shared(dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let parent = match dir.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => return,
+    };
+    if let Ok(metadata) = std::fs::metadata(parent) {
+        let mode = metadata.permissions().mode();
+        if mode & 0o022 != 0 && mode & 0o1000 == 0 {
+            tracing::warn!(
+                "The database directory {:?} is in {:?}, which other users can write to (mode {:o})",
+                dir,
+                parent,
+                mode & 0o777
+            );
+        }
+    }
+}
+
+/// Delete every row of every table in the database as part of the given transaction.
+pub fn clear_all_tables(txn: &Transaction) -> DatabaseResult<()> {
+    // Foreign keys are only checked on commit, when every table is empty.
+    txn.pragma_update(None, "defer_foreign_keys", true)?;
+    for table in table_names(txn)? {
+        txn.execute(&format!("DELETE FROM \"{}\"", table), [])?;
+    }
+    Ok(())
+}
+
+/// The names of all tables in the database, excluding SQLite's internal tables.
+pub(crate) fn table_names(txn: &Transaction) -> rusqlite::Result<Vec<String>> {
+    txn.prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+        .query_map([], |row| row.get(0))?
+        .collect()
+}
+
 pub fn num_read_threads() -> usize {
     let num_cpus = num_cpus::get();
     let num_threads = num_cpus.checked_div(2).unwrap_or(0);
@@ -0,0 +1,106 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Counts of the transactions run through a database handle and the time
+/// spent in them, not including time waiting for a permit.
+/// Only `async_reader`, `async_commit` and `async_commit_in_place` are counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbMetrics {
+    /// The number of read transactions.
+    pub reads: u64,
+    /// Total microseconds spent in read transactions.
+    pub read_micros: u64,
+    /// The number of write transactions.
+    pub writes: u64,
+    /// Total microseconds spent in write transactions.
+    pub write_micros: u64,
+    /// The number of commits that went over a [`QuotaMode::SoftWarn`] quota.
+    pub quota_warnings: u64,
+}
+
+/// Receives the transactions run through the databases it is installed on
+/// with [`DbWrite::set_metrics_sink`], e.g. to aggregate them per [`DbName`].
+///
+/// It is called right after each transaction, so it should be cheap.
+pub trait StateMetricsSink: Send + Sync {
+    /// A read transaction on `db` took `micros`.
+    fn record_read(&self, db: &DbKind, micros: u64);
+
+    /// A write transaction on `db` took `micros` and grew the bytes of pages
+    /// in use by `bytes`.
+    fn record_write(&self, db: &DbKind, micros: u64, bytes: u64);
+
+    /// Flushing a source chain wrote `num_ops` ops to `db` in `micros`.
+    fn record_flush(&self, db: &DbKind, micros: u64, num_ops: u64);
+}
+
+#[derive(Default)]
+pub(super) struct DbMetricsCounters {
+    reads: AtomicU64,
+    read_micros: AtomicU64,
+    writes: AtomicU64,
+    write_micros: AtomicU64,
+    quota_warnings: AtomicU64,
+    sink: parking_lot::RwLock<Option<Arc<dyn StateMetricsSink>>>,
+}
+
+impl DbMetricsCounters {
+    pub(super) fn record_read(&self, db: &impl DbKindT, elapsed: Duration) {
+        let micros = elapsed.as_micros() as u64;
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.read_micros.fetch_add(micros, Ordering::Relaxed);
+        if let Some(sink) = &*self.sink.read() {
+            sink.record_read(&db.kind(), micros);
+        }
+    }
+
+    pub(super) fn record_write(&self, db: &impl DbKindT, elapsed: Duration, bytes: u64) {
+        let micros = elapsed.as_micros() as u64;
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        self.write_micros.fetch_add(micros, Ordering::Relaxed);
+        if let Some(sink) = &*self.sink.read() {
+            sink.record_write(&db.kind(), micros, bytes);
+        }
+    }
+
+    /// Whether there is a sink to measure the bytes written for.
+    pub(super) fn has_sink(&self) -> bool {
+        self.sink.read().is_some()
+    }
+
+    pub(super) fn record_quota_warning(&self) {
+        self.quota_warnings.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<Kind: DbKindT> DbRead<Kind> {
+    /// The transactions run through this database handle and its clones so far.
+    pub fn metrics(&self) -> DbMetrics {
+        let m = &self.metrics;
+        DbMetrics {
+            reads: m.reads.load(Ordering::Relaxed),
+            read_micros: m.read_micros.load(Ordering::Relaxed),
+            writes: m.writes.load(Ordering::Relaxed),
+            write_micros: m.write_micros.load(Ordering::Relaxed),
+            quota_warnings: m.quota_warnings.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<Kind: DbKindT> DbWrite<Kind> {
+    /// Set or remove the sink that this database and all clones of this
+    /// handle report their transactions to.
+    pub fn set_metrics_sink(&self, sink: Option<Arc<dyn StateMetricsSink>>) {
+        *self.metrics.sink.write() = sink;
+    }
+
+    /// Report to the metrics sink, if there is one, that flushing a source
+    /// chain wrote `num_ops` ops to this database in `elapsed`.
+    pub fn record_flush(&self, elapsed: Duration, num_ops: u64) {
+        if let Some(sink) = &*self.metrics.sink.read() {
+            sink.record_flush(&self.kind().kind(), elapsed.as_micros() as u64, num_ops);
+        }
+    }
+}
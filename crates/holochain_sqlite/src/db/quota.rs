@@ -0,0 +1,178 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// A limit on the space a single database may use.
+///
+/// Quotas apply per database file, so limiting a cell's cache never blocks
+/// writes to its authored chain. Set a quota only on the databases that
+/// should be limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbQuota {
+    /// The number of bytes of pages in use that a commit may leave behind.
+    pub limit_bytes: u64,
+    /// What happens to a commit that goes over the limit.
+    pub mode: QuotaMode,
+}
+
+/// How a [`DbQuota`] is enforced.
+///
+/// Only commits that grow a database over its limit are affected, so data
+/// can always be deleted from a database that is already over its quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuotaMode {
+    /// The transaction is rolled back and [`DatabaseError::QuotaExceeded`]
+    /// is returned.
+    HardFail,
+    /// The transaction is committed and a warning is logged and counted in
+    /// [`DbMetrics::quota_warnings`].
+    SoftWarn,
+}
+
+/// The kind of a database without the space it belongs to, for naming
+/// databases in configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DbName {
+    /// See [`DbKind::Authored`].
+    Authored,
+    /// See [`DbKind::Dht`].
+    Dht,
+    /// See [`DbKind::Cache`].
+    Cache,
+    /// See [`DbKind::Conductor`].
+    Conductor,
+    /// See [`DbKind::Wasm`].
+    Wasm,
+    /// See [`DbKind::P2pAgentStore`].
+    P2pAgentStore,
+    /// See [`DbKind::P2pMetrics`].
+    P2pMetrics,
+}
+
+impl DbKind {
+    /// The name of this kind of database.
+    pub fn name(&self) -> DbName {
+        match self {
+            DbKind::Authored(_) => DbName::Authored,
+            DbKind::Dht(_) => DbName::Dht,
+            DbKind::Cache(_) => DbName::Cache,
+            DbKind::Conductor => DbName::Conductor,
+            DbKind::Wasm => DbName::Wasm,
+            DbKind::P2pAgentStore(_) => DbName::P2pAgentStore,
+            DbKind::P2pMetrics(_) => DbName::P2pMetrics,
+        }
+    }
+}
+
+/// Frees space in a database that a commit is about to take over its quota.
+///
+/// It runs inside the transaction being committed and is given the number of
+/// bytes the database is over its limit by. Anything it deletes is committed
+/// along with the rest of the transaction.
+pub type QuotaEviction = Arc<dyn Fn(&Transaction, u64) -> DatabaseResult<()> + Send + Sync>;
+
+/// The quota of a database and how to make room under it.
+#[derive(Clone, Default)]
+pub(super) struct QuotaState {
+    quota: Option<DbQuota>,
+    eviction: Option<QuotaEviction>,
+}
+
+impl<Kind: DbKindT> DbRead<Kind> {
+    /// The quota currently set on this database, if any.
+    pub fn quota(&self) -> Option<DbQuota> {
+        self.quota.read().quota
+    }
+}
+
+impl<Kind: DbKindT> DbWrite<Kind> {
+    /// Set or remove the quota for this database.
+    /// The quota is shared with all clones of this handle and is checked
+    /// by every later `async_commit` and `async_commit_in_place`.
+    pub fn set_quota(&self, quota: Option<DbQuota>) {
+        self.quota.write().quota = quota;
+    }
+
+    /// Set how to free space when a commit would take this database over its
+    /// quota. The eviction runs before the quota is enforced, and if it frees
+    /// enough space the commit goes ahead as usual.
+    pub fn set_quota_eviction(
+        &self,
+        eviction: impl Fn(&Transaction, u64) -> DatabaseResult<()> + Send + Sync + 'static,
+    ) {
+        self.quota.write().eviction = Some(Arc::new(eviction));
+    }
+
+    pub(super) fn quota_state(&self) -> QuotaState {
+        self.quota.read().clone()
+    }
+}
+
+/// The bytes of pages in use as seen by this transaction, including any
+/// writes it has not yet committed.
+pub(super) fn used_bytes(txn: &Transaction) -> DatabaseResult<u64> {
+    let pragma = |name: &str| txn.pragma_query_value(None, name, |row| row.get::<_, u64>(0));
+    Ok((pragma("page_count")? - pragma("freelist_count")?) * pragma("page_size")?)
+}
+
+impl QuotaState {
+    /// The space in use at the start of a write transaction, if there is a
+    /// quota to check it against or it is to be measured anyway.
+    pub(super) fn usage_before(
+        &self,
+        txn: &Transaction,
+        measure: bool,
+    ) -> DatabaseResult<Option<u64>> {
+        (self.quota.is_some() || measure)
+            .then(|| used_bytes(txn))
+            .transpose()
+    }
+
+    /// Check a write transaction against the quota before it is committed,
+    /// given the space the database was using before the transaction.
+    pub(super) fn enforce(
+        &self,
+        txn: &Transaction,
+        before: Option<u64>,
+        db_kind: &DbKind,
+        metrics: &DbMetricsCounters,
+    ) -> DatabaseResult<()> {
+        let (quota, before) = match (self.quota, before) {
+            (Some(quota), Some(before)) => (quota, before),
+            _ => return Ok(()),
+        };
+        let within = |used: u64| used <= quota.limit_bytes || used <= before;
+        let mut used = used_bytes(txn)?;
+        if let Some(eviction) = &self.eviction {
+            // Pages are only freed once they are empty, so keep evicting
+            // for as long as it makes progress.
+            while !within(used) {
+                eviction(txn, used - quota.limit_bytes)?;
+                let after = used_bytes(txn)?;
+                if after >= used {
+                    break;
+                }
+                used = after;
+            }
+        }
+        if within(used) {
+            return Ok(());
+        }
+        match quota.mode {
+            QuotaMode::HardFail => Err(DatabaseError::QuotaExceeded {
+                db_kind: db_kind.to_string(),
+                used,
+                limit: quota.limit_bytes,
+            }),
+            QuotaMode::SoftWarn => {
+                tracing::warn!(
+                    "The {} database is using {} bytes, over its quota of {}",
+                    db_kind,
+                    used,
+                    quota.limit_bytes
+                );
+                metrics.record_quota_warning();
+                Ok(())
+            }
+        }
+    }
+}
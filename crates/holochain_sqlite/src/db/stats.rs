@@ -0,0 +1,98 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Space usage of a database as reported by SQLite.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbStats {
+    /// The size of a page in bytes.
+    pub page_size: u64,
+    /// The number of pages in the database file.
+    pub page_count: u64,
+    /// The number of unused pages in the database file.
+    pub freelist_count: u64,
+    /// Usage of each table, keyed by table name.
+    pub tables: BTreeMap<String, TableStats>,
+    /// The quota set on this database, to compare against [`DbStats::used_bytes`].
+    pub quota: Option<DbQuota>,
+}
+
+/// Space usage of a single table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableStats {
+    /// The number of rows in the table.
+    pub rows: u64,
+    /// Bytes of pages used by the table and its indexes.
+    pub bytes: u64,
+}
+
+impl DbStats {
+    /// The size of the database file in bytes.
+    pub fn size_bytes(&self) -> u64 {
+        self.page_size * self.page_count
+    }
+
+    /// The bytes of pages in use, which is what a [`DbQuota`] limits.
+    pub fn used_bytes(&self) -> u64 {
+        self.page_size * (self.page_count - self.freelist_count)
+    }
+
+    /// The percentage of pages in the file that are in use.
+    pub fn percent_used(&self) -> f64 {
+        if self.page_count == 0 {
+            return 0.0;
+        }
+        (self.page_count - self.freelist_count) as f64 / self.page_count as f64 * 100.0
+    }
+}
+
+impl<Kind: DbKindT> DbRead<Kind> {
+    /// Gather [`DbStats`] for this database within a single read transaction.
+    pub async fn stats(&self) -> DatabaseResult<DbStats> {
+        let quota = self.quota();
+        self.async_reader(move |txn| {
+            let pragma = |name: &str| txn.pragma_query_value(None, name, |row| row.get(0));
+            let page_size = pragma("page_size")?;
+            let page_count = pragma("page_count")?;
+            let freelist_count = pragma("freelist_count")?;
+
+            // Index pages are attributed to the table they index.
+            let mut bytes_by_table = txn
+                .prepare(
+                    "
+                    SELECT sqlite_master.tbl_name, SUM(dbstat.pgsize)
+                    FROM dbstat
+                    JOIN sqlite_master ON dbstat.name = sqlite_master.name
+                    GROUP BY sqlite_master.tbl_name
+                    ",
+                )?
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?
+                .collect::<Result<HashMap<String, u64>, _>>()?;
+
+            let mut tables = BTreeMap::new();
+            for name in table_names(&txn)? {
+                let rows =
+                    txn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", name), [], |row| {
+                        row.get(0)
+                    })?;
+                let table_bytes = bytes_by_table.remove(&name).unwrap_or(0);
+                tables.insert(
+                    name,
+                    TableStats {
+                        rows,
+                        bytes: table_bytes,
+                    },
+                );
+            }
+
+            DatabaseResult::Ok(DbStats {
+                page_size,
+                page_count,
+                freelist_count,
+                tables,
+                quota,
+            })
+        })
+        .await
+    }
+}
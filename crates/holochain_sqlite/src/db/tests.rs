@@ -1,8 +1,6 @@
-use tempfile::TempDir;
+use crate::prelude::*;
 
-use crate::prelude::DatabaseResult;
-
-use super::{num_read_threads, DbKind, DbWrite};
+use super::{num_read_threads, DbKindConductor, DbKindT, DbKindWasm, DbRead, DbWrite};
 
 /// This test does prove that making all transactions
 /// synchronous fixes the db timeout issue but it's slow
@@ -10,8 +8,8 @@ use super::{num_read_threads, DbKind, DbWrite};
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "This is too slow for CI as it has to wait for the timeouts"]
 async fn db_connection_doesnt_timeout() {
-    let td = TempDir::new("lots_of_dbs").unwrap();
-    let db = DbWrite::test(&td, DbKind::Wasm).unwrap();
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
     let num_readers = num_read_threads() * 2;
     let mut jhs = Vec::new();
 
@@ -136,3 +134,476 @@ async fn db_connection_doesnt_timeout() {
     // without taking permits.
     assert!(result.is_err());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn db_can_be_reopened_after_close() {
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::open(td.path(), DbKindWasm).unwrap();
+    db.async_commit(|txn| {
+        txn.execute(
+            "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+            [vec![0], vec![0]],
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+
+    let path = db.path().clone();
+    db.close();
+    std::fs::remove_file(&path).unwrap();
+
+    // Reopening at the same path must initialize a fresh database
+    // rather than handing back the closed handle.
+    let db = DbWrite::open(td.path(), DbKindWasm).unwrap();
+    let count: usize = db
+        .async_reader(|txn| {
+            let count = txn.query_row("SELECT COUNT(rowid) FROM Wasm", [], |row| row.get(0))?;
+            DatabaseResult::Ok(count)
+        })
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dbs_of_the_same_kind_have_separate_permits() {
+    let td1 = tempfile::tempdir().unwrap();
+    let td2 = tempfile::tempdir().unwrap();
+    let db1 = DbWrite::test(td1.path(), DbKindWasm).unwrap();
+    let db2 = DbWrite::test(td2.path(), DbKindWasm).unwrap();
+
+    let _permit = db1.conn_write_permit().await;
+    // This would never resolve if both databases shared a write semaphore.
+    tokio::time::timeout(std::time::Duration::from_secs(5), db2.conn_write_permit())
+        .await
+        .expect("Writing to one database blocked writes to another");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backup_and_restore() {
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    let insert = |hash: u8| {
+        db.async_commit(move |txn| {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![hash], vec![0]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+    };
+    insert(1).await.unwrap();
+
+    let backup_dir = tempfile::tempdir().unwrap();
+    let backup = backup_dir.path().join("backup.sqlite3");
+    db.backup_to(&backup).await.unwrap();
+
+    // Changes after the backup must not show up in the restored database.
+    insert(2).await.unwrap();
+
+    let restore_dir = tempfile::tempdir().unwrap();
+    let restored = DbWrite::restore_from(&backup, restore_dir.path(), DbKindWasm).unwrap();
+    let hashes: Vec<Vec<u8>> = restored
+        .async_reader(|txn| {
+            let mut stmt = txn.prepare("SELECT hash FROM Wasm")?;
+            let hashes = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            DatabaseResult::Ok(hashes)
+        })
+        .await
+        .unwrap();
+    assert_eq!(hashes, vec![vec![1]]);
+
+    // Restoring over an existing database is refused.
+    assert!(matches!(
+        DbWrite::restore_from(&backup, restore_dir.path(), DbKindWasm),
+        Err(DatabaseError::DatabaseAlreadyExists(_))
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restore_refuses_backup_of_another_kind() {
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    let backup_dir = tempfile::tempdir().unwrap();
+    let backup = backup_dir.path().join("backup.sqlite3");
+    db.backup_to(&backup).await.unwrap();
+
+    let restore_dir = tempfile::tempdir().unwrap();
+    let restore_prefix = restore_dir.path().join("databases");
+    match DbWrite::restore_from(&backup, &restore_prefix, DbKindConductor) {
+        Err(DatabaseError::BackupMissingTables { missing, .. }) => {
+            assert_eq!(missing, vec!["BlockSpan", "ConductorState", "Nonce"])
+        }
+        r => panic!("expected missing tables, got {:?}", r.map(|_| ())),
+    }
+    assert!(!restore_prefix.join(DbKindConductor.filename()).exists());
+
+    // A backup of the right kind is restored into a directory prepared
+    // like the one a normal open creates.
+    let restored = DbWrite::restore_from(&backup, &restore_prefix, DbKindWasm).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(restored.path().parent().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stats_report_table_usage() {
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    const NUM_ROWS: u8 = 100;
+    const BLOB_SIZE: usize = 1000;
+    db.async_commit(|txn| {
+        for i in 0..NUM_ROWS {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![i], vec![0; BLOB_SIZE]],
+            )?;
+        }
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+
+    let stats = db.stats().await.unwrap();
+    let wasm = &stats.tables["Wasm"];
+    assert_eq!(wasm.rows, NUM_ROWS as u64);
+    let expected = NUM_ROWS as u64 * BLOB_SIZE as u64;
+    assert!(wasm.bytes >= expected && wasm.bytes < expected * 4);
+    assert_eq!(stats.tables["DnaDef"].rows, 0);
+    assert!(stats.size_bytes() >= wasm.bytes);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clear_all_only_clears_that_database() {
+    let td1 = tempfile::tempdir().unwrap();
+    let td2 = tempfile::tempdir().unwrap();
+    let cleared = DbWrite::test(td1.path(), DbKindWasm).unwrap();
+    let kept = DbWrite::test(td2.path(), DbKindWasm).unwrap();
+    for db in [&cleared, &kept] {
+        db.async_commit(|txn| {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![0], vec![0]],
+            )?;
+            txn.execute(
+                "INSERT INTO DnaDef (hash, blob) VALUES(?, ?)",
+                [vec![0], vec![0]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    cleared.clear_all().await.unwrap();
+
+    let rows = |db: DbWrite<DbKindWasm>| async move {
+        let stats = db.stats().await.unwrap();
+        (stats.tables["Wasm"].rows, stats.tables["DnaDef"].rows)
+    };
+    assert_eq!(rows(cleared).await, (0, 0));
+    assert_eq!(rows(kept).await, (1, 1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn read_only_db_can_read_but_not_write() {
+    let td = tempfile::tempdir().unwrap();
+
+    assert!(matches!(
+        DbRead::open_read_only(td.path(), DbKindWasm),
+        Err(DatabaseError::DatabaseMissing(_))
+    ));
+
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    db.async_commit(|txn| {
+        txn.execute(
+            "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+            [vec![0], vec![0]],
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+
+    // The writable handle is still open while we read.
+    let read_only = DbRead::open_read_only(td.path(), DbKindWasm).unwrap();
+    let count: usize = read_only
+        .async_reader(|txn| {
+            DatabaseResult::Ok(txn.query_row("SELECT COUNT(*) FROM Wasm", [], |row| row.get(0))?)
+        })
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    let err = read_only
+        .conn()
+        .unwrap()
+        .execute(
+            "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+            [vec![1], vec![0]],
+        )
+        .unwrap_err();
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ReadOnly));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metrics_count_transactions_per_database() {
+    let td1 = tempfile::tempdir().unwrap();
+    let td2 = tempfile::tempdir().unwrap();
+    let db = DbWrite::test(td1.path(), DbKindWasm).unwrap();
+    let other = DbWrite::test(td2.path(), DbKindWasm).unwrap();
+
+    for i in 0..3 {
+        db.async_commit(move |txn| {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![i], vec![0]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+    // Clones share the counters.
+    db.clone()
+        .async_reader(|txn| {
+            DatabaseResult::Ok(txn.query_row("SELECT COUNT(*) FROM Wasm", [], |row| {
+                row.get::<_, usize>(0)
+            })?)
+        })
+        .await
+        .unwrap();
+
+    let metrics = db.metrics();
+    assert_eq!((metrics.reads, metrics.writes), (1, 3));
+    assert_eq!(other.metrics(), Default::default());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metrics_sink_attributes_transactions_per_database() {
+    let td = tempfile::tempdir().unwrap();
+    let wasm = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    let conductor = DbWrite::test(td.path(), DbKindConductor).unwrap();
+    let sink = std::sync::Arc::new(AggregatingMetricsSink::default());
+    wasm.set_metrics_sink(Some(sink.clone()));
+    conductor.set_metrics_sink(Some(sink.clone()));
+
+    for i in 0..3 {
+        wasm.async_commit(move |txn| {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![i], vec![0; 64 * 1024]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+    wasm.record_flush(std::time::Duration::from_millis(1), 5);
+    conductor
+        .async_commit(|txn| {
+            txn.execute(
+                "INSERT INTO ConductorState (id, blob) VALUES(1, ?)",
+                [vec![0; 16 * 1024]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+    conductor
+        .async_reader(|txn| {
+            DatabaseResult::Ok(
+                txn.query_row("SELECT COUNT(*) FROM ConductorState", [], |row| {
+                    row.get::<_, usize>(0)
+                })?,
+            )
+        })
+        .await
+        .unwrap();
+
+    let wasm_metrics = sink.get(DbName::Wasm);
+    assert_eq!((wasm_metrics.reads, wasm_metrics.writes), (0, 3));
+    assert!(wasm_metrics.write_bytes >= 3 * 64 * 1024);
+    assert_eq!((wasm_metrics.flushes, wasm_metrics.flush_micros), (1, 1000));
+    assert_eq!(wasm_metrics.flushed_ops, 5);
+
+    let conductor_metrics = sink.get(DbName::Conductor);
+    assert_eq!((conductor_metrics.reads, conductor_metrics.writes), (1, 1));
+    // Sizes are counted in whole pages, and part of a blob may fit into a
+    // page that is already in use.
+    assert!(conductor_metrics.write_bytes >= 8 * 1024);
+    assert!(conductor_metrics.write_bytes < wasm_metrics.write_bytes);
+    assert_eq!(conductor_metrics.flushes, 0);
+
+    // Once the sink is removed, transactions are no longer reported to it.
+    wasm.clone().set_metrics_sink(None);
+    wasm.async_commit(|txn| {
+        txn.execute("DELETE FROM Wasm", [])?;
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(sink.get(DbName::Wasm), wasm_metrics);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn db_directory_is_prepared() {
+    let td = tempfile::tempdir().unwrap();
+    let prefix = td.path().join("new").join("conductor");
+    let db = DbWrite::test(&prefix, DbKindWasm).unwrap();
+    let dir = db.path().parent().unwrap();
+    assert!(dir.is_dir());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // The parents created along the way are private too.
+        for created in [dir, &prefix, &td.path().join("new")] {
+            let mode = std::fs::metadata(created).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700, "{:?}", created);
+        }
+    }
+
+    // Reopening the same directory is fine.
+    DbWrite::test(&prefix, DbKindWasm).unwrap();
+    assert!(!dir.join(".write-probe").exists());
+
+    // An existing directory that can't be written to is refused up front.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let read_only_prefix = td.path().join("read_only");
+        let read_only = read_only_prefix.join("wasm");
+        std::fs::create_dir_all(&read_only).unwrap();
+        std::fs::set_permissions(&read_only, std::fs::Permissions::from_mode(0o500)).unwrap();
+        // Root can write to it anyway, so there is nothing to check.
+        if std::fs::write(read_only.join("root"), b"").is_err() {
+            assert!(matches!(
+                DbWrite::test(&read_only_prefix, DbKindWasm),
+                Err(DatabaseError::PermissionDenied(path)) if path == read_only
+            ));
+        }
+        std::fs::set_permissions(&read_only, std::fs::Permissions::from_mode(0o700)).unwrap();
+    }
+
+    let file = td.path().join("file");
+    std::fs::write(&file, b"").unwrap();
+    assert!(matches!(
+        DbWrite::test(&file, DbKindWasm),
+        Err(DatabaseError::NotADirectory(path)) if path == file
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quota_is_checked_before_commit() {
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    let insert = |db: DbWrite<DbKindWasm>, i: u8| async move {
+        db.async_commit(move |txn| {
+            txn.execute(
+                "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                [vec![i], vec![0; 100_000]],
+            )?;
+            DatabaseResult::Ok(())
+        })
+        .await
+    };
+
+    insert(db.clone(), 0).await.unwrap();
+    let used = db.stats().await.unwrap().used_bytes();
+
+    // A clone shares the quota.
+    db.clone().set_quota(Some(DbQuota {
+        limit_bytes: used,
+        mode: QuotaMode::SoftWarn,
+    }));
+    insert(db.clone(), 1).await.unwrap();
+    assert_eq!(db.metrics().quota_warnings, 1);
+
+    let used = db.stats().await.unwrap().used_bytes();
+    db.set_quota(Some(DbQuota {
+        limit_bytes: used,
+        mode: QuotaMode::HardFail,
+    }));
+    assert!(matches!(
+        insert(db.clone(), 2).await,
+        Err(DatabaseError::QuotaExceeded { limit, .. }) if limit == used
+    ));
+    // The failed write was rolled back.
+    let count = db
+        .async_reader(|txn| {
+            DatabaseResult::Ok(txn.query_row("SELECT COUNT(*) FROM Wasm", [], |row| {
+                row.get::<_, usize>(0)
+            })?)
+        })
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    // A commit that doesn't grow the database goes through even while the
+    // database is over its quota.
+    db.set_quota(Some(DbQuota {
+        limit_bytes: 0,
+        mode: QuotaMode::HardFail,
+    }));
+    db.async_commit(|txn| {
+        txn.execute("DELETE FROM Wasm WHERE hash = ?", [vec![0]])?;
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+
+    // Evicting makes room for a commit that would go over.
+    let used = db.stats().await.unwrap().used_bytes();
+    db.set_quota(Some(DbQuota {
+        limit_bytes: used,
+        mode: QuotaMode::HardFail,
+    }));
+    db.set_quota_eviction(|txn, _| {
+        txn.execute("DELETE FROM Wasm WHERE hash != ?", [vec![2]])?;
+        Ok(())
+    });
+    insert(db.clone(), 2).await.unwrap();
+    let hashes = db
+        .async_reader(|txn| {
+            DatabaseResult::Ok(
+                txn.prepare("SELECT hash FROM Wasm")?
+                    .query_map([], |row| row.get::<_, Vec<u8>>(0))?
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(hashes, vec![vec![2]]);
+    assert_eq!(db.stats().await.unwrap().quota, db.quota());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reopened_db_shares_the_write_permit_of_live_clones() {
+    let td = tempfile::tempdir().unwrap();
+    let db = DbWrite::open(td.path(), DbKindWasm).unwrap();
+    let clone = db.clone();
+    db.close();
+    let reopened = DbWrite::open(td.path(), DbKindWasm).unwrap();
+
+    // While the clone of the closed handle is writing,
+    // the reopened handle can't start a write of its own.
+    let permit = clone.conn_write_permit().await;
+    assert!(tokio::time::timeout(
+        std::time::Duration::from_millis(100),
+        reopened.conn_write_permit()
+    )
+    .await
+    .is_err());
+
+    drop(permit);
+    reopened.conn_write_permit().await;
+}
@@ -17,6 +17,35 @@ pub enum DatabaseError {
     #[error("database directory does not exist at configured path: {0}")]
     DatabaseMissing(PathBuf),
 
+    #[error("database path is not a directory: {0}")]
+    NotADirectory(PathBuf),
+
+    #[error("permission denied for database directory: {0}")]
+    PermissionDenied(PathBuf),
+
+    #[error("A database already exists at path: {0}")]
+    DatabaseAlreadyExists(PathBuf),
+
+    #[error("The {db_kind} database is at schema version {found}, but only versions up to {supported} are supported")]
+    SchemaVersionTooNew {
+        db_kind: String,
+        found: usize,
+        supported: usize,
+    },
+
+    #[error("The {db_kind} database would use {used} bytes, over its quota of {limit}")]
+    QuotaExceeded {
+        db_kind: String,
+        used: u64,
+        limit: u64,
+    },
+
+    #[error("The backup of the {db_kind} database is missing the tables: {}", missing.join(", "))]
+    BackupMissingTables {
+        db_kind: String,
+        missing: Vec<String>,
+    },
+
     #[error(
         "Attempted to access a private entry in a context where no private database is specified: {0}"
     )]
@@ -17,6 +17,7 @@ use once_cell::sync::Lazy;
 use rusqlite::{Connection, Transaction};
 
 use crate::db::DbKind;
+use crate::error::{DatabaseError, DatabaseResult};
 
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| Schema {
     migrations: vec![
@@ -25,6 +26,10 @@ pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| Schema {
             forward: include_str!("sql/cell/schema/1-up.sql").into(),
             _schema: include_str!("sql/cell/schema/1.sql").into(),
         },
+        M {
+            forward: include_str!("sql/cell/schema/2-up.sql").into(),
+            _schema: include_str!("sql/cell/schema/2.sql").into(),
+        },
     ],
 });
 
@@ -39,7 +44,13 @@ pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| Schema {
 });
 
 pub static SCHEMA_WASM: Lazy<Schema> = Lazy::new(|| Schema {
-    migrations: vec![M::initial(include_str!("sql/wasm/schema/0.sql"))],
+    migrations: vec![
+        M::initial(include_str!("sql/wasm/schema/0.sql")),
+        M {
+            forward: include_str!("sql/wasm/schema/1-up.sql").into(),
+            _schema: include_str!("sql/wasm/schema/1.sql").into(),
+        },
+    ],
 });
 
 pub static SCHEMA_P2P_STATE: Lazy<Schema> = Lazy::new(|| Schema {
@@ -59,11 +70,9 @@ impl Schema {
     /// The decision is based on the difference between this Schema's
     /// current_index and the user_version pragma value in the database itself.
     /// NB: The current_index is 0-based, and the user_version is 1-based.
-    pub fn initialize(
-        &self,
-        conn: &mut Connection,
-        db_kind: Option<DbKind>,
-    ) -> rusqlite::Result<()> {
+    /// A database migrated by a newer version of this code is refused rather
+    /// than opened with a schema we don't know about.
+    pub fn initialize(&self, conn: &mut Connection, db_kind: Option<DbKind>) -> DatabaseResult<()> {
         let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
         let db_kind = db_kind
             .as_ref()
@@ -97,7 +106,11 @@ impl Schema {
                 );
             }
             std::cmp::Ordering::Greater => {
-                unimplemented!("backward migrations unimplemented");
+                return Err(DatabaseError::SchemaVersionTooNew {
+                    db_kind,
+                    found: migrations_applied,
+                    supported: num_migrations,
+                });
             }
         }
 
@@ -132,7 +145,6 @@ type Sql = String;
 #[cfg(test)]
 mod tests {
     use super::*;
-
     #[test]
     fn test_migrations_initial() {
         let schema = Schema {
@@ -200,4 +212,27 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_migrations_refuse_newer_version() {
+        let schema = Schema {
+            migrations: vec![M::initial("CREATE TABLE Numbers (num INTEGER);")],
+        };
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+
+        assert!(matches!(
+            schema.initialize(&mut conn, None),
+            Err(DatabaseError::SchemaVersionTooNew {
+                found: 2,
+                supported: 1,
+                ..
+            })
+        ));
+        // Nothing was run against the database.
+        assert!(conn
+            .execute("INSERT INTO Numbers (num) VALUES (1)", ())
+            .is_err());
+    }
 }
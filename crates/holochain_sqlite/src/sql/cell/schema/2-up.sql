@@ -0,0 +1,4 @@
+ALTER TABLE
+  Action
+ADD
+  COLUMN last_accessed INTEGER NULL;
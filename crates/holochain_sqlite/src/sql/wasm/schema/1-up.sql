@@ -0,0 +1,10 @@
+ALTER TABLE
+  DnaDef
+ADD
+  COLUMN name TEXT;
+ALTER TABLE
+  DnaDef
+ADD
+  COLUMN network_seed TEXT;
+CREATE INDEX IF NOT EXISTS DnaDef_name_idx ON DnaDef(name);
+CREATE INDEX IF NOT EXISTS DnaDef_network_seed_idx ON DnaDef(network_seed);
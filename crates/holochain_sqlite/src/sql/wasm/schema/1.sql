@@ -0,0 +1,25 @@
+-- no-sql-format --
+
+-- Holochain Wasm schema with DnaDef lookup by name and network seed
+
+CREATE TABLE IF NOT EXISTS Wasm (
+    hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    blob            BLOB           NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS DnaDef (
+    hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    blob            BLOB           NOT NULL,
+
+    -- Copied out of the blob for lookups.
+    -- NULL for rows stored before this migration until they are reindexed.
+    name            TEXT           NULL,
+    network_seed    TEXT           NULL
+);
+CREATE INDEX IF NOT EXISTS DnaDef_name_idx ON DnaDef(name);
+CREATE INDEX IF NOT EXISTS DnaDef_network_seed_idx ON DnaDef(network_seed);
+
+CREATE TABLE IF NOT EXISTS EntryDef (
+    key             BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    blob            BLOB           NOT NULL
+);
@@ -22,3 +22,68 @@ macro_rules! print_stmts_test {
         r
     }};
 }
+
+use crate::db::{DbKind, DbName, StateMetricsSink};
+use std::collections::HashMap;
+
+/// A [`StateMetricsSink`] that adds up the transactions it is given per
+/// [`DbName`].
+#[derive(Default)]
+pub struct AggregatingMetricsSink(parking_lot::Mutex<HashMap<DbName, DbNameMetrics>>);
+
+/// The totals of an [`AggregatingMetricsSink`] for one kind of database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DbNameMetrics {
+    /// The number of read transactions.
+    pub reads: u64,
+    /// Total microseconds spent in read transactions.
+    pub read_micros: u64,
+    /// The number of write transactions.
+    pub writes: u64,
+    /// Total microseconds spent in write transactions.
+    pub write_micros: u64,
+    /// Total bytes the write transactions grew the databases by.
+    pub write_bytes: u64,
+    /// The number of source chain flushes.
+    pub flushes: u64,
+    /// Total microseconds spent flushing source chains.
+    pub flush_micros: u64,
+    /// Total ops written by source chain flushes.
+    pub flushed_ops: u64,
+}
+
+impl AggregatingMetricsSink {
+    /// The totals for the databases named `db` so far.
+    pub fn get(&self, db: DbName) -> DbNameMetrics {
+        self.0.lock().get(&db).copied().unwrap_or_default()
+    }
+
+    fn update(&self, db: &DbKind, f: impl FnOnce(&mut DbNameMetrics)) {
+        f(self.0.lock().entry(db.name()).or_default())
+    }
+}
+
+impl StateMetricsSink for AggregatingMetricsSink {
+    fn record_read(&self, db: &DbKind, micros: u64) {
+        self.update(db, |m| {
+            m.reads += 1;
+            m.read_micros += micros;
+        })
+    }
+
+    fn record_write(&self, db: &DbKind, micros: u64, bytes: u64) {
+        self.update(db, |m| {
+            m.writes += 1;
+            m.write_micros += micros;
+            m.write_bytes += bytes;
+        })
+    }
+
+    fn record_flush(&self, db: &DbKind, micros: u64, num_ops: u64) {
+        self.update(db, |m| {
+            m.flushes += 1;
+            m.flush_micros += micros;
+            m.flushed_ops += num_ops;
+        })
+    }
+}
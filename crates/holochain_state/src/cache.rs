@@ -0,0 +1,223 @@
+//! Keeping the cache database within a size budget.
+//!
+//! Everything the cascade fetches from the network is stored in the cache,
+//! so without eviction it only ever grows. The cascade marks cached actions
+//! with [`mark_accessed`] whenever it stores or reads them, and eviction
+//! removes the actions accessed longest ago first.
+
+use holo_hash::ActionHash;
+use holo_hash::AnyDhtHash;
+use holo_hash::EntryHash;
+use holochain_sqlite::prelude::*;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::Timestamp;
+
+use crate::mutations::StateMutationResult;
+
+/// The outcome of [`evict_least_recently_used`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvictionReport {
+    /// The number of actions removed, along with their ops and receipts.
+    pub actions: usize,
+    /// The number of entries removed because no remaining action used them.
+    pub entries: usize,
+    /// The approximate size of the cached data after eviction.
+    pub bytes: u64,
+}
+
+/// Record that the action with this hash, or the actions of the entry with
+/// this hash, were stored or read in the cache at `at`.
+pub fn mark_accessed(txn: &Transaction, hash: &AnyDhtHash, at: Timestamp) -> DatabaseResult<()> {
+    txn.execute(
+        "
+        UPDATE Action SET last_accessed = :at
+        WHERE hash = :hash OR entry_hash = :hash
+        ",
+        named_params! {
+            ":at": at,
+            ":hash": hash,
+        },
+    )?;
+    Ok(())
+}
+
+/// Remove the actions accessed longest ago from the cache, along with their
+/// ops and any entries nothing else refers to, until the cached actions and
+/// entries take up no more than `budget` bytes.
+///
+/// Actions that have not been accessed since the cache was migrated to
+/// record access times are removed first.
+///
+/// This takes a [`DbKindCache`] database so it can never be run against
+/// authored or DHT data.
+pub async fn evict_least_recently_used(
+    cache: &DbWrite<DbKindCache>,
+    budget: u64,
+) -> StateMutationResult<EvictionReport> {
+    Ok(cache
+        .async_commit(move |txn| evict_least_recently_used_txn(txn, budget))
+        .await?)
+}
+
+/// Evict from a cache database that a commit is taking `excess` bytes over
+/// its quota, for use with [`DbWrite::set_quota_eviction`].
+///
+/// This removes at least `excess` bytes of the data accessed longest ago.
+/// The database may shrink by less than that, as the pages the data was on
+/// are only freed once they are empty.
+pub fn evict_for_quota(txn: &Transaction, excess: u64) -> DatabaseResult<()> {
+    let bytes = cached_bytes(txn)?;
+    evict_least_recently_used_txn(txn, bytes.saturating_sub(excess))?;
+    Ok(())
+}
+
+fn cached_bytes(txn: &Transaction) -> DatabaseResult<u64> {
+    Ok(txn.query_row(
+        "
+        SELECT
+        (SELECT IFNULL(SUM(length(blob)), 0) FROM Action)
+        + (SELECT IFNULL(SUM(length(blob)), 0) FROM Entry)
+        ",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+fn evict_least_recently_used_txn(txn: &Transaction, budget: u64) -> DatabaseResult<EvictionReport> {
+    let mut report = EvictionReport {
+        bytes: cached_bytes(txn)?,
+        ..Default::default()
+    };
+    if report.bytes <= budget {
+        return Ok(report);
+    }
+
+    // Actions never accessed have a null access time, which sorts first.
+    let by_last_accessed: Vec<(ActionHash, u64, Option<EntryHash>)> = txn
+        .prepare(
+            "
+            SELECT hash, length(blob), entry_hash
+            FROM Action
+            ORDER BY last_accessed ASC
+            ",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+
+    for (action_hash, action_bytes, entry_hash) in by_last_accessed {
+        if report.bytes <= budget {
+            break;
+        }
+        // Ops and their receipts cascade from the action.
+        txn.execute(
+            "DELETE FROM Action WHERE hash = :hash",
+            named_params! {
+                ":hash": action_hash
+            },
+        )?;
+        report.actions += 1;
+        report.bytes = report.bytes.saturating_sub(action_bytes);
+
+        if let Some(entry_hash) = entry_hash {
+            let entry_bytes: Option<u64> = txn
+                .query_row(
+                    "
+                    DELETE FROM Entry WHERE hash = :hash
+                    AND NOT EXISTS(SELECT 1 FROM Action WHERE entry_hash = :hash)
+                    RETURNING length(blob)
+                    ",
+                    named_params! {
+                        ":hash": entry_hash
+                    },
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(entry_bytes) = entry_bytes {
+                report.entries += 1;
+                report.bytes = report.bytes.saturating_sub(entry_bytes);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::insert_op;
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+
+    fn store_record_op(entry: &Entry) -> DhtOpHashed {
+        let mut create = fixt!(Create);
+        create.entry_hash = EntryHash::with_data_sync(entry);
+        DhtOpHashed::from_content_sync(DhtOp::StoreRecord(
+            fixt!(Signature),
+            Action::Create(create),
+            Some(Box::new(entry.clone())),
+        ))
+    }
+
+    fn count(db: &DbWrite<DbKindCache>, table: &str) -> usize {
+        db.conn().unwrap().with_reader_test(|txn| {
+            txn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn least_recently_accessed_are_evicted_first() {
+        let test_db = crate::test_utils::test_cache_db();
+        let db = test_db.to_db();
+
+        let entries: Vec<Entry> = (0..3u8)
+            .map(|i| {
+                Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(
+                    vec![i; 1000],
+                ))))
+            })
+            .collect();
+        let ops: Vec<DhtOpHashed> = entries.iter().map(store_record_op).collect();
+        // The second op was accessed last, then the third, then the first.
+        let accessed_at = [1, 3, 2];
+
+        db.test_commit(|txn| {
+            for (op, micros) in ops.iter().zip(accessed_at) {
+                insert_op(txn, op).unwrap();
+                let action_hash = ActionHash::with_data_sync(&op.action());
+                mark_accessed(txn, &action_hash.into(), Timestamp::from_micros(micros)).unwrap();
+            }
+        });
+
+        let report = evict_least_recently_used(&db, u64::MAX).await.unwrap();
+        assert_eq!(report.actions, 0);
+
+        // Going one byte over budget evicts the first op's action and entry.
+        let report = evict_least_recently_used(&db, report.bytes - 1)
+            .await
+            .unwrap();
+        assert_eq!((report.actions, report.entries), (1, 1));
+
+        // Then the third.
+        let report = evict_least_recently_used(&db, report.bytes - 1)
+            .await
+            .unwrap();
+        assert_eq!((report.actions, report.entries), (1, 1));
+
+        let remaining = ActionHash::with_data_sync(&ops[1].action());
+        db.conn().unwrap().with_reader_test(|txn| {
+            let hash: ActionHash = txn
+                .query_row("SELECT hash FROM Action", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(hash, remaining);
+        });
+
+        // No ops or entries are left without their action.
+        assert_eq!(count(&db, "DhtOp"), 1);
+        assert_eq!(count(&db, "Entry"), 1);
+    }
+}
@@ -56,6 +56,126 @@ pub fn contains(txn: &Transaction<'_>, hash: &DnaHash) -> StateQueryResult<bool>
     )?)
 }
 
+/// The hashes of every stored DnaDef with this name.
+pub fn get_hashes_by_name(txn: &Transaction<'_>, name: &str) -> StateQueryResult<Vec<DnaHash>> {
+    get_hashes_where(txn, "name", name)
+}
+
+/// The hashes of every stored DnaDef with this network seed.
+pub fn get_hashes_by_network_seed(
+    txn: &Transaction<'_>,
+    network_seed: &str,
+) -> StateQueryResult<Vec<DnaHash>> {
+    get_hashes_where(txn, "network_seed", network_seed)
+}
+
+fn get_hashes_where(
+    txn: &Transaction<'_>,
+    column: &str,
+    value: &str,
+) -> StateQueryResult<Vec<DnaHash>> {
+    let mut stmt = txn.prepare(&format!(
+        "SELECT hash FROM DnaDef WHERE {} = :value",
+        column
+    ))?;
+    let hashes = stmt
+        .query_map(named_params! { ":value": value }, |row| row.get("hash"))?
+        .collect::<Result<_, _>>()?;
+    Ok(hashes)
+}
+
+/// Fill in the name and network seed of DnaDefs stored before these
+/// were copied out of the blob, so they can be found by
+/// [`get_hashes_by_name`] and [`get_hashes_by_network_seed`].
+/// Returns the number of rows updated.
+pub fn reindex(txn: &mut Transaction) -> StateMutationResult<usize> {
+    let unindexed = txn
+        .prepare("SELECT hash, blob FROM DnaDef WHERE name IS NULL")?
+        .query_and_then([], |row| {
+            let hash: DnaHash = row.get("hash")?;
+            let dna_def: DnaDef = from_blob(row.get("blob")?)?;
+            StateQueryResult::Ok((hash, dna_def))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (hash, dna_def) in &unindexed {
+        txn.execute(
+            "UPDATE DnaDef SET name = :name, network_seed = :network_seed WHERE hash = :hash",
+            named_params! {
+                ":name": dna_def.name,
+                ":network_seed": dna_def.modifiers.network_seed,
+                ":hash": hash,
+            },
+        )?;
+    }
+    Ok(unindexed.len())
+}
+
 pub fn put(txn: &mut Transaction, dna_def: DnaDef) -> StateMutationResult<()> {
     mutations::insert_dna_def(txn, &DnaDefHashed::from_content_sync(dna_def))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holo_hash::HasHash;
+    use holochain_sqlite::prelude::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn dna_defs_can_be_found_by_name_and_network_seed() {
+        let db = crate::test_utils::test_wasm_db();
+        let dna_def = |name: &str, network_seed: &str| {
+            let mut dna_def = DnaDef::unique_from_zomes(vec![], vec![]);
+            dna_def.name = name.into();
+            dna_def.modifiers.network_seed = network_seed.into();
+            DnaDefHashed::from_content_sync(dna_def)
+        };
+        let dna_defs = [
+            dna_def("a", "1"),
+            dna_def("a", "2"),
+            dna_def("b", "2"),
+            dna_def("b", "3"),
+        ];
+        let hashes = |indices: &[usize]| -> HashSet<DnaHash> {
+            indices
+                .iter()
+                .map(|i| dna_defs[*i].as_hash().clone())
+                .collect()
+        };
+
+        db.test_commit(|txn| {
+            for dna_def in &dna_defs {
+                mutations::insert_dna_def(txn, dna_def).unwrap();
+            }
+            // Forget the index of the last two, as if they were stored before it existed.
+            txn.execute(
+                "UPDATE DnaDef SET name = NULL, network_seed = NULL WHERE name = 'b'",
+                [],
+            )
+            .unwrap();
+        });
+        let by_name = |name: &str| -> HashSet<DnaHash> {
+            fresh_reader_test!(db, |txn| get_hashes_by_name(&txn, name)
+                .unwrap()
+                .into_iter()
+                .collect())
+        };
+        let by_network_seed = |network_seed: &str| -> HashSet<DnaHash> {
+            fresh_reader_test!(db, |txn| get_hashes_by_network_seed(&txn, network_seed)
+                .unwrap()
+                .into_iter()
+                .collect())
+        };
+
+        assert_eq!(by_name("a"), hashes(&[0, 1]));
+        assert_eq!(by_name("b"), hashes(&[]));
+        assert_eq!(by_network_seed("2"), hashes(&[1]));
+
+        assert_eq!(db.test_commit(|txn| reindex(txn).unwrap()), 2);
+        assert_eq!(db.test_commit(|txn| reindex(txn).unwrap()), 0);
+
+        assert_eq!(by_name("b"), hashes(&[2, 3]));
+        assert_eq!(by_network_seed("2"), hashes(&[1, 2]));
+        assert_eq!(by_network_seed("4"), hashes(&[]));
+    }
+}
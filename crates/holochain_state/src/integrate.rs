@@ -1,12 +1,13 @@
-use holo_hash::{AnyLinkableHash, DhtOpHash, HasHash};
+use holo_hash::{AgentPubKey, AnyLinkableHash, DhtOpHash, EntryHash, HasHash};
+use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pDnaT;
-use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::rusqlite::{named_params, Transaction};
 use holochain_types::{
-    db_cache::DhtDbQueryCache,
+    db_cache::{error::DbCacheError, DhtDbQueryCache},
     dht_op::{DhtOp, DhtOpHashed, DhtOpType},
     prelude::DhtOpResult,
 };
-use holochain_zome_types::{EntryVisibility, SignedAction};
+use holochain_zome_types::{EntryVisibility, SignedAction, Timestamp, ValidationStatus};
 
 use crate::{prelude::*, query::get_public_op_from_db};
 
@@ -87,6 +88,148 @@ pub async fn authored_ops_to_dht_db_without_check(
     Ok(())
 }
 
+/// Options for [`bulk_import_ops`].
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// The number of ops written in each transaction.
+    pub batch_size: usize,
+    /// Check each op's action signature and entry hash before importing it.
+    /// Only disable this for ops from a trusted source, such as a backup of
+    /// this node's own data.
+    pub verify: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1000,
+            verify: true,
+        }
+    }
+}
+
+/// The outcome of [`bulk_import_ops`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// The number of ops written to the database.
+    pub imported: usize,
+    /// The number of ops that were already in the database, or were
+    /// `StoreEntry` ops for private entries.
+    pub skipped: usize,
+    /// Ops that were not imported because their hash, signature or entry
+    /// didn't match their content.
+    pub invalid: Vec<DhtOpHash>,
+}
+
+/// Write ops that have already been validated elsewhere, such as when
+/// restoring from a peer's export, straight into the dht database as
+/// integrated.
+///
+/// This goes around the validation and integration workflows, so each op
+/// is stored with the validation status it was given. Every batch is written
+/// in one transaction, and each op is written along with its action and
+/// entry, so an import that stops part way leaves every imported op complete.
+/// Ops already in the database are skipped, so an import can be re-run.
+pub async fn bulk_import_ops(
+    dht_db: &DbWrite<DbKindDht>,
+    dht_db_cache: &DhtDbQueryCache,
+    ops: impl IntoIterator<Item = (DhtOpHash, DhtOp, ValidationStatus)>,
+    options: ImportOptions,
+) -> StateMutationResult<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut all_activity = Vec::new();
+    let mut ops = ops.into_iter().peekable();
+    while ops.peek().is_some() {
+        let batch_size = options.batch_size.max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        for (hash, op, status) in ops.by_ref().take(batch_size) {
+            if !options.verify || op_is_genuine(&hash, &op).await {
+                batch.push((DhtOpHashed::with_pre_hashed(op, hash), status));
+            } else {
+                report.invalid.push(hash);
+            }
+        }
+
+        let batch_len = batch.len();
+        let (imported, activity) = dht_db
+            .async_commit(move |txn| {
+                let time = Timestamp::now();
+                let mut imported = 0;
+                let mut activity = Vec::new();
+                for (op, status) in batch {
+                    // Peers never send private entries, but don't store one
+                    // if an export contains it.
+                    if is_private_store_entry(op.as_content()) || dht_op_exists(txn, op.as_hash())?
+                    {
+                        continue;
+                    }
+                    let op = filter_private_entry(op)?;
+                    let hash = op.as_hash();
+                    insert_op(txn, &op)?;
+                    set_validation_status(txn, hash, status)?;
+                    set_validation_stage(txn, hash, ValidationLimboStatus::Pending)?;
+                    set_when_integrated(txn, hash, time)?;
+                    imported += 1;
+                    if op.get_type() == DhtOpType::RegisterAgentActivity {
+                        let action = op.action();
+                        activity.push((action.author().clone(), action.action_seq()));
+                    }
+                }
+                StateMutationResult::Ok((imported, activity))
+            })
+            .await?;
+        report.skipped += batch_len - imported;
+        report.imported += imported;
+        all_activity.extend(activity);
+    }
+
+    // The cache only accepts each author's activity in consecutive order,
+    // which the import can't be relied on to be in.
+    all_activity.sort_unstable();
+    all_activity.dedup();
+    let mut all_activity = all_activity.into_iter().peekable();
+    while let Some((author, seq)) = all_activity.next() {
+        let mut seqs = vec![seq];
+        while let Some((_, seq)) = all_activity.next_if(|(a, _)| *a == author) {
+            seqs.push(seq);
+        }
+        add_imported_activity(dht_db_cache, &author, seqs).await?;
+    }
+    Ok(report)
+}
+
+/// Mark an author's imported activity, in ascending order, as integrated
+/// in the cache up to the first gap.
+async fn add_imported_activity(
+    dht_db_cache: &DhtDbQueryCache,
+    author: &AgentPubKey,
+    seqs: Vec<u32>,
+) -> StateMutationResult<()> {
+    // The cache may have been loaded after the import was committed, in
+    // which case it already covers some of this activity.
+    let mut integrated = dht_db_cache.integrated_upper_bound(author).await?;
+    for seq in seqs {
+        if integrated.map_or(false, |integrated| seq <= integrated) {
+            continue;
+        }
+        match dht_db_cache.set_activity_to_integrated(author, seq).await {
+            Ok(()) => integrated = Some(seq),
+            Err(DbCacheError::ActivityOutOfOrder(prev, next)) => {
+                tracing::warn!(
+                    ?author,
+                    "Imported activity {} doesn't follow integrated activity {}, \
+                    so the rest of this author's imported activity is left out of the cache",
+                    next,
+                    prev
+                );
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
 fn insert_locally_validated_op(
     txn: &mut Transaction,
     op: DhtOpHashed,
@@ -148,3 +291,167 @@ fn is_private_store_entry(op: &DhtOp) -> bool {
         .map_or(false, |et| *et.visibility() == EntryVisibility::Private)
         && op.get_type() == DhtOpType::StoreEntry
 }
+
+/// Check that an op hashes to the given hash, its action is signed by its
+/// author and any entry matches the action's entry hash.
+async fn op_is_genuine(hash: &DhtOpHash, op: &DhtOp) -> bool {
+    let action = op.action();
+    DhtOpHash::with_data_sync(op) == *hash
+        && op.entry().map_or(true, |entry| {
+            action.entry_hash() == Some(&EntryHash::with_data_sync(entry))
+        })
+        && action
+            .author()
+            .verify_signature(op.signature(), &action)
+            .await
+}
+
+fn dht_op_exists(txn: &Transaction, hash: &DhtOpHash) -> StateMutationResult<bool> {
+    Ok(txn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM DhtOp WHERE hash = :hash)",
+        named_params! {
+            ":hash": hash
+        },
+        |row| row.get(0),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::StateQueryResult;
+    use crate::test_utils::{test_dht_db, test_keystore};
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+
+    fn count_integrated(db: &DbWrite<DbKindDht>) -> usize {
+        db.conn().unwrap().with_reader_test(|txn| {
+            txn.query_row(
+                "
+                SELECT COUNT(*) FROM DhtOp
+                JOIN Action ON DhtOp.action_hash = Action.hash
+                WHERE DhtOp.when_integrated IS NOT NULL
+                AND DhtOp.validation_stage IS NULL
+                ",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bulk_import_is_idempotent() {
+        let test_db = test_dht_db();
+        let db = test_db.to_db();
+        let cache = DhtDbQueryCache::new(db.clone().into());
+
+        let ops: Vec<_> = (0..10_000)
+            .map(|_| {
+                let op = DhtOp::RegisterAgentActivity(fixt!(Signature), fixt!(Action));
+                (DhtOpHash::with_data_sync(&op), op, ValidationStatus::Valid)
+            })
+            .collect();
+        let options = ImportOptions {
+            verify: false,
+            ..Default::default()
+        };
+
+        let report = bulk_import_ops(&db, &cache, ops.clone(), options.clone())
+            .await
+            .unwrap();
+        assert_eq!((report.imported, report.skipped), (10_000, 0));
+        assert_eq!(count_integrated(&db), 10_000);
+
+        let report = bulk_import_ops(&db, &cache, ops, options).await.unwrap();
+        assert_eq!((report.imported, report.skipped), (0, 10_000));
+        assert_eq!(count_integrated(&db), 10_000);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bulk_import_integrates_shuffled_activity_in_order() {
+        use rand::seq::SliceRandom;
+
+        let test_db = test_dht_db();
+        let db = test_db.to_db();
+        let cache = DhtDbQueryCache::new(db.clone().into());
+        let alice = fixt!(AgentPubKey);
+        let bob = fixt!(AgentPubKey);
+
+        // Load the cache before importing so it has to be updated in order.
+        assert_eq!(cache.integrated_upper_bound(&alice).await.unwrap(), None);
+
+        let activity = |author: &AgentPubKey, seq: u32| {
+            let mut create = fixt!(Create);
+            create.author = author.clone();
+            create.action_seq = seq;
+            let op = DhtOp::RegisterAgentActivity(fixt!(Signature), Action::Create(create));
+            (DhtOpHash::with_data_sync(&op), op, ValidationStatus::Valid)
+        };
+        // Bob's activity is missing seq 2.
+        let mut ops: Vec<_> = (0..10)
+            .map(|seq| activity(&alice, seq))
+            .chain([0, 1, 3, 4].into_iter().map(|seq| activity(&bob, seq)))
+            .collect();
+        ops.shuffle(&mut rand::thread_rng());
+        let options = ImportOptions {
+            verify: false,
+            batch_size: 3,
+        };
+
+        let report = bulk_import_ops(&db, &cache, ops, options).await.unwrap();
+        assert_eq!(report.imported, 14);
+        assert_eq!(cache.integrated_upper_bound(&alice).await.unwrap(), Some(9));
+        assert_eq!(cache.integrated_upper_bound(&bob).await.unwrap(), Some(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bulk_import_rejects_tampered_ops() {
+        let test_db = test_dht_db();
+        let db = test_db.to_db();
+        let cache = DhtDbQueryCache::new(db.clone().into());
+        let keystore = test_keystore();
+        let author = keystore.new_sign_keypair_random().await.unwrap();
+
+        let mut create = fixt!(Create);
+        create.author = author;
+        let action = Action::Create(create);
+        let signature =
+            SignedActionHashed::sign(&keystore, ActionHashed::from_content_sync(action.clone()))
+                .await
+                .unwrap()
+                .signature()
+                .clone();
+        let genuine = DhtOp::RegisterAgentActivity(signature.clone(), action.clone());
+
+        // Changing the action invalidates the signature even if the op is rehashed.
+        let mut tampered = action;
+        if let Action::Create(create) = &mut tampered {
+            create.action_seq = create.action_seq.wrapping_add(1);
+        }
+        let tampered = DhtOp::RegisterAgentActivity(signature, tampered);
+        let tampered_hash = DhtOpHash::with_data_sync(&tampered);
+
+        let ops = vec![
+            (
+                DhtOpHash::with_data_sync(&genuine),
+                genuine,
+                ValidationStatus::Valid,
+            ),
+            (tampered_hash.clone(), tampered, ValidationStatus::Valid),
+        ];
+        let report = bulk_import_ops(&db, &cache, ops, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.invalid, vec![tampered_hash.clone()]);
+
+        let found = db
+            .async_reader(move |txn| {
+                StateQueryResult::Ok(dht_op_exists(&txn, &tampered_hash).unwrap())
+            })
+            .await
+            .unwrap();
+        assert!(!found);
+    }
+}
@@ -0,0 +1,208 @@
+//! Proactive checks of the data stored in a cell database (authored, DHT or cache).
+//!
+//! [`integrity_check`] never writes, so it is safe to run against a database
+//! that is in use, including one opened with `DbRead::open_read_only`.
+
+use crate::query::from_blob;
+use crate::query::StateQueryResult;
+use crate::validation_receipts::SignedValidationReceipt;
+use holo_hash::ActionHash;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::prelude::*;
+use std::collections::BTreeMap;
+
+/// Checks that a blob deserializes as the type stored in its table.
+type BlobCheck = fn(Vec<u8>) -> bool;
+
+/// The tables holding serialized blobs, with the check for each.
+const BLOB_TABLES: &[(&str, BlobCheck)] = &[
+    ("Action", |blob| from_blob::<SignedAction>(blob).is_ok()),
+    ("Entry", |blob| from_blob::<Entry>(blob).is_ok()),
+    ("ValidationReceipt", |blob| {
+        from_blob::<SignedValidationReceipt>(blob).is_ok()
+    }),
+];
+
+/// The outcome of an [`integrity_check`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Problems reported by SQLite's `integrity_check` and `foreign_key_check` pragmas.
+    pub sqlite_errors: Vec<String>,
+    /// The result of deserializing every blob, keyed by table name.
+    pub tables: BTreeMap<String, TableIntegrity>,
+    /// Actions that reference an entry which is not in the `Entry` table.
+    /// This is expected in DHT and cache databases, which may hold an action
+    /// without its entry, but not in an authored database.
+    pub missing_entries: Vec<ActionHash>,
+}
+
+/// The result of deserializing the blobs of a single table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableIntegrity {
+    /// The number of rows that deserialized as expected.
+    pub valid: usize,
+    /// The raw `hash` keys of the rows that failed to deserialize.
+    pub invalid: Vec<Vec<u8>>,
+}
+
+impl IntegrityReport {
+    /// True if no problems were found.
+    /// Missing entries are not counted, see [`IntegrityReport::missing_entries`].
+    pub fn is_ok(&self) -> bool {
+        self.sqlite_errors.is_empty() && self.tables.values().all(|t| t.invalid.is_empty())
+    }
+}
+
+/// Check every row of a cell database within the given read transaction.
+pub fn integrity_check(txn: &Transaction) -> StateQueryResult<IntegrityReport> {
+    let mut report = IntegrityReport::default();
+
+    let mut stmt = txn.prepare("PRAGMA integrity_check")?;
+    for message in stmt.query_map([], |row| row.get::<_, String>(0))? {
+        let message = message?;
+        if message != "ok" {
+            report.sqlite_errors.push(message);
+        }
+    }
+    let mut stmt = txn.prepare("PRAGMA foreign_key_check")?;
+    for violation in stmt.query_map([], |row| {
+        // The rowid is NULL for WITHOUT ROWID tables.
+        let rowid = row
+            .get::<_, Option<i64>>(1)?
+            .map_or_else(|| "none".to_string(), |rowid| rowid.to_string());
+        Ok(format!(
+            "row {} of {} violates a foreign key into {}",
+            rowid,
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(2)?,
+        ))
+    })? {
+        report.sqlite_errors.push(violation?);
+    }
+
+    for (table, is_valid) in BLOB_TABLES {
+        let mut integrity = TableIntegrity::default();
+        let mut stmt = txn.prepare(&format!("SELECT hash, blob FROM {}", table))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let hash: Vec<u8> = row.get(0)?;
+            if is_valid(row.get(1)?) {
+                integrity.valid += 1;
+            } else {
+                integrity.invalid.push(hash);
+            }
+        }
+        report.tables.insert(table.to_string(), integrity);
+    }
+
+    report.missing_entries = txn
+        .prepare(
+            "
+            SELECT Action.hash FROM Action
+            LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+            WHERE Action.entry_hash IS NOT NULL AND Entry.hash IS NULL
+            ",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::{insert_action, insert_entry};
+    use fixt::prelude::*;
+
+    #[test]
+    fn integrity_check_pinpoints_bad_rows() {
+        let test_db = crate::test_utils::test_authored_db();
+        let db = test_db.to_db();
+
+        let entry = fixt!(Entry);
+        let entry_hash = EntryHash::with_data_sync(&entry);
+        let mut with_entry = fixt!(Create);
+        with_entry.entry_hash = entry_hash.clone();
+        let with_entry = SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(Action::Create(with_entry)),
+            fixt!(Signature),
+        );
+        let without_entry = SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(Action::Create(fixt!(Create))),
+            fixt!(Signature),
+        );
+
+        db.test_commit(|txn| {
+            insert_action(txn, &with_entry).unwrap();
+            insert_entry(txn, &entry_hash, &entry).unwrap();
+            insert_action(txn, &without_entry).unwrap();
+        });
+
+        let report = db
+            .conn()
+            .unwrap()
+            .with_reader_test(|txn| integrity_check(&txn).unwrap());
+        assert!(report.is_ok());
+        assert_eq!(report.tables["Action"].valid, 2);
+        assert_eq!(report.tables["Entry"].valid, 1);
+        assert_eq!(
+            report.missing_entries,
+            vec![without_entry.as_hash().clone()]
+        );
+
+        // Truncate the serialized action.
+        db.test_commit(|txn| {
+            txn.execute(
+                "UPDATE Action SET blob = substr(blob, 1, length(blob) / 2) WHERE hash = ?",
+                [with_entry.as_hash()],
+            )
+            .unwrap();
+        });
+
+        let report = db
+            .conn()
+            .unwrap()
+            .with_reader_test(|txn| integrity_check(&txn).unwrap());
+        assert!(!report.is_ok());
+        assert_eq!(report.tables["Action"].valid, 1);
+        assert_eq!(
+            report.tables["Action"].invalid,
+            vec![with_entry.as_hash().get_raw_39().to_vec()]
+        );
+    }
+
+    #[test]
+    fn integrity_check_reports_foreign_key_violations_without_rowid() {
+        let test_db = crate::test_utils::test_authored_db();
+        let db = test_db.to_db();
+
+        {
+            let conn = db.conn().unwrap();
+            conn.pragma_update(None, "foreign_keys", "OFF".to_string())
+                .unwrap();
+            conn.execute_batch(
+                "
+                CREATE TABLE Parent (id INTEGER PRIMARY KEY);
+                CREATE TABLE Child (
+                    id INTEGER PRIMARY KEY,
+                    parent INTEGER REFERENCES Parent(id)
+                ) WITHOUT ROWID;
+                INSERT INTO Child VALUES (1, 2);
+                ",
+            )
+            .unwrap();
+            conn.pragma_update(None, "foreign_keys", "ON".to_string())
+                .unwrap();
+        }
+
+        let report = db
+            .conn()
+            .unwrap()
+            .with_reader_test(|txn| integrity_check(&txn).unwrap());
+        assert_eq!(
+            report.sqlite_errors,
+            vec!["row none of Child violates a foreign key into Parent".to_string()]
+        );
+    }
+}
@@ -29,12 +29,14 @@
 
 #[allow(missing_docs)]
 pub mod block;
+pub mod cache;
 pub mod chain_lock;
 #[allow(missing_docs)]
 pub mod dna_def;
 pub mod entry_def;
 pub mod host_fn_workspace;
 pub mod integrate;
+pub mod integrity;
 pub mod mutations;
 pub mod nonce;
 #[allow(missing_docs)]
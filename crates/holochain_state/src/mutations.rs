@@ -233,6 +233,8 @@ pub fn insert_dna_def(txn: &mut Transaction, dna_def: &DnaDefHashed) -> StateMut
     sql_insert!(txn, DnaDef, {
         "hash": hash,
         "blob": to_blob(dna_def)?,
+        "name": dna_def.name,
+        "network_seed": dna_def.modifiers.network_seed,
     })?;
     Ok(())
 }
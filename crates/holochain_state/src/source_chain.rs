@@ -320,7 +320,9 @@ impl SourceChain {
         // Write the entries, actions and ops to the database in one transaction.
         let author = self.author.clone();
         let persisted_head = self.persisted_head.clone();
-        match self
+        let num_ops = ops.len() as u64;
+        let start = std::time::Instant::now();
+        let result = self
             .vault
             .async_commit(move |txn: &mut Transaction| {
                 let now = Timestamp::now();
@@ -373,8 +375,11 @@ impl SourceChain {
                 }
                 SourceChainResult::Ok(actions)
             })
-            .await
-        {
+            .await;
+        if result.is_ok() {
+            self.vault.record_flush(start.elapsed(), num_ops);
+        }
+        match result {
             Err(SourceChainError::HeadMoved(
                 actions,
                 entries,
@@ -999,17 +1004,29 @@ async fn rebase_actions_on(
     mut rebase_timestamp: Timestamp,
 ) -> Result<Vec<SignedActionHashed>, ScratchError> {
     actions.sort_by_key(|shh| shh.action().action_seq());
-    for shh in actions.iter_mut() {
+    let mut rebased = Vec::with_capacity(actions.len());
+    for shh in actions {
         let mut action = shh.action().clone();
         action.rebase_on(rebase_action.clone(), rebase_seq, rebase_timestamp)?;
         rebase_seq = action.action_seq();
         rebase_timestamp = action.timestamp();
         let hh = ActionHashed::from_content_sync(action);
         rebase_action = hh.as_hash().clone();
-        let new_shh = SignedActionHashed::sign(keystore, hh).await?;
-        *shh = new_shh;
+        rebased.push(hh);
+    }
+    // The hashes chain but the signatures don't, so sign them all at once.
+    let mut requests = Vec::with_capacity(rebased.len());
+    for hh in &rebased {
+        let data =
+            holochain_serialized_bytes::encode(hh.as_content()).map_err(one_err::OneErr::new)?;
+        requests.push((hh.author().clone(), data.into()));
     }
-    Ok(actions)
+    let signatures = keystore.sign_batch(requests).await;
+    rebased
+        .into_iter()
+        .zip(signatures)
+        .map(|(hh, signature)| Ok(SignedActionHashed::with_presigned(hh, signature?)))
+        .collect()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1436,6 +1453,66 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn flushes_are_reported_to_the_metrics_sink_of_the_authored_db() -> SourceChainResult<()>
+    {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        let sink = Arc::new(AggregatingMetricsSink::default());
+        db.set_metrics_sink(Some(sink.clone()));
+        dht_db.to_db().set_metrics_sink(Some(sink.clone()));
+
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let new_chain = || {
+            SourceChain::new(
+                db.clone(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+        };
+        let (chain_1, chain_2) = (new_chain().await?, new_chain().await?);
+        let action_builder = builder::CloseChain {
+            new_dna_hash: fixt!(DnaHash),
+        };
+        for chain in [&chain_1, &chain_2] {
+            chain
+                .put(action_builder.clone(), None, ChainTopOrdering::Strict)
+                .await?;
+        }
+
+        chain_1.flush(&mock).await?;
+        // A flush that fails to write is not counted.
+        assert!(chain_2.flush(&mock).await.is_err());
+
+        let authored = sink.get(DbName::Authored);
+        assert_eq!((authored.flushes, authored.flushed_ops), (1, 2));
+        assert!(authored.writes > 0);
+        assert_eq!(sink.get(DbName::Dht).flushes, 0);
+
+        Ok(())
+    }
     #[tokio::test(flavor = "multi_thread")]
     async fn test_relaxed_ordering_with_entry() -> SourceChainResult<()> {
         let test_db = test_authored_db();
@@ -129,6 +129,22 @@ pub fn test_keystore() -> holochain_keystore::MetaLairClient {
     .expect("timeout elapsed")
 }
 
+/// Generate a mock keystore whose agent keys, and the signatures made with
+/// them, are derived from `seed`.
+pub fn test_keystore_deterministic(
+    seed: [u8; 32],
+) -> holochain_keystore::mock_keystore::MockKeystore {
+    tokio_helper::block_on(
+        async move {
+            holochain_keystore::mock_keystore::spawn_mock_keystore(seed)
+                .await
+                .unwrap()
+        },
+        std::time::Duration::from_secs(1),
+    )
+    .expect("timeout elapsed")
+}
+
 /// A test database in a temp directory
 #[derive(Shrinkwrap)]
 pub struct TestDb<Kind: DbKindT> {
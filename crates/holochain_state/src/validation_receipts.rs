@@ -1,16 +1,14 @@
 //! Module for items related to aggregating validation_receipts
 
-use futures::Stream;
-use futures::StreamExt;
-use futures::TryStreamExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use holo_hash::AgentPubKey;
 use holo_hash::DhtOpHash;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_keystore::MetaLairClient;
 use holochain_serialized_bytes::prelude::*;
-use holochain_sqlite::prelude::*;
 use holochain_sqlite::rusqlite::named_params;
-use holochain_sqlite::rusqlite::OptionalExtension;
 use holochain_sqlite::rusqlite::Transaction;
 use holochain_zome_types::signature::Signature;
 use holochain_zome_types::Timestamp;
@@ -57,25 +55,20 @@ impl ValidationReceipt {
         if self.validators.is_empty() {
             return Ok(None);
         }
-        let this = self.clone();
-        // Try to sign with all validators but silently fail on
-        // any that cannot sign.
-        // If all signatures fail then return an error.
-        let futures = self
-            .validators
-            .iter()
-            .map(|validator| {
-                let this = this.clone();
-                let validator = validator.clone();
-                let keystore = keystore.clone();
-                async move { validator.sign(&keystore, this).await }
-            })
-            .collect::<Vec<_>>();
-        let stream = futures::stream::iter(futures);
-        let signatures = try_stream_of_results(stream).await?;
-        if signatures.is_empty() {
-            unreachable!("Signatures cannot be empty because the validators vec is not empty");
-        }
+        let data: Arc<[u8]> = holochain_serialized_bytes::encode(&self)
+            .map_err(one_err::OneErr::new)?
+            .into();
+        // Every validator must be able to sign.
+        let signatures = keystore
+            .sign_batch(
+                self.validators
+                    .iter()
+                    .map(|validator| (validator.clone(), data.clone()))
+                    .collect(),
+            )
+            .await
+            .into_iter()
+            .collect::<holochain_keystore::LairResult<Vec<_>>>()?;
         Ok(Some(SignedValidationReceipt {
             receipt: self,
             validators_signatures: signatures,
@@ -83,15 +76,6 @@ impl ValidationReceipt {
     }
 }
 
-/// Try to collect a stream of futures that return results into a vec.
-async fn try_stream_of_results<T, U, E>(stream: U) -> Result<Vec<T>, E>
-where
-    U: Stream,
-    <U as Stream>::Item: futures::Future<Output = Result<T, E>>,
-{
-    stream.buffer_unordered(10).map(|r| r).try_collect().await
-}
-
 /// A full, signed validation receipt.
 #[derive(
     Debug,
@@ -113,6 +97,38 @@ pub struct SignedValidationReceipt {
     pub validators_signatures: Vec<Signature>,
 }
 
+impl SignedValidationReceipt {
+    /// Check that every validator of the receipt has signed its content.
+    ///
+    /// The signatures must be in the same order as the validators, as
+    /// [`ValidationReceipt::sign`] produces them, and no validator may be
+    /// listed twice.
+    pub async fn verify(&self) -> bool {
+        let validators = &self.receipt.validators;
+        if validators.is_empty() || validators.len() != self.validators_signatures.len() {
+            return false;
+        }
+        if validators.iter().collect::<HashSet<_>>().len() != validators.len() {
+            return false;
+        }
+        let data: Arc<[u8]> = match holochain_serialized_bytes::encode(&self.receipt) {
+            Ok(data) => data.into(),
+            Err(_) => return false,
+        };
+        futures::future::join_all(
+            validators
+                .iter()
+                .zip(&self.validators_signatures)
+                .map(|(validator, signature)| {
+                    validator.verify_signature_raw(signature, data.clone())
+                }),
+        )
+        .await
+        .into_iter()
+        .all(|valid| valid)
+    }
+}
+
 pub fn list_receipts(
     txn: &Transaction,
     op_hash: &DhtOpHash,
@@ -131,24 +147,40 @@ pub fn list_receipts(
     iter.collect()
 }
 
-pub fn count_valid(txn: &Transaction, op_hash: &DhtOpHash) -> DatabaseResult<usize> {
-    let count: usize = txn
-        .query_row(
-            "SELECT COUNT(hash) FROM ValidationReceipt WHERE op_hash = :op_hash",
-            named_params! {
-                ":op_hash": op_hash
-            },
-            |row| row.get(0),
-        )
-        .optional()?
-        .unwrap_or(0);
-    Ok(count)
+/// The number of distinct validators that have receipted this op.
+/// A validator listed on several receipts is only counted once.
+pub fn count_valid(txn: &Transaction, op_hash: &DhtOpHash) -> StateQueryResult<usize> {
+    Ok(receipted_validators(txn, op_hash)?.len())
 }
 
+/// Every validator listed on a stored receipt for this op.
+fn receipted_validators(
+    txn: &Transaction,
+    op_hash: &DhtOpHash,
+) -> StateQueryResult<HashSet<AgentPubKey>> {
+    Ok(list_receipts(txn, op_hash)?
+        .into_iter()
+        .flat_map(|r| r.receipt.validators)
+        .collect())
+}
+
+/// Add a receipt unless every validator on it has already receipted this op.
+/// Authorities may send a receipt for an op more than once, and each time it
+/// can carry a different `when_integrated` or a different group of
+/// validators, so the receipt hash alone doesn't catch the duplicate.
 pub fn add_if_unique(
     txn: &mut Transaction,
     receipt: SignedValidationReceipt,
 ) -> StateMutationResult<()> {
+    let receipted = receipted_validators(txn, &receipt.receipt.dht_op_hash)?;
+    if receipt
+        .receipt
+        .validators
+        .iter()
+        .all(|validator| receipted.contains(validator))
+    {
+        return Ok(());
+    }
     mutations::insert_validation_receipt(txn, receipt)
 }
 
@@ -157,6 +189,7 @@ mod tests {
     use super::*;
     use fixt::prelude::*;
     use holo_hash::HasHash;
+    use holochain_sqlite::prelude::*;
     use holochain_types::dht_op::DhtOp;
     use holochain_types::dht_op::DhtOpHashed;
     use holochain_zome_types::fixt::*;
@@ -231,29 +264,143 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_try_stream_of_results() {
-        let iter: Vec<futures::future::Ready<Result<i32, String>>> = vec![];
-        let stream = futures::stream::iter(iter);
-        assert_eq!(Ok(vec![]), try_stream_of_results(stream).await);
-
-        let iter = vec![async move { Result::<_, String>::Ok(0) }];
-        let stream = futures::stream::iter(iter);
-        assert_eq!(Ok(vec![0]), try_stream_of_results(stream).await);
-
-        let iter = (0..10).map(|i| async move { Result::<_, String>::Ok(i) });
-        let stream = futures::stream::iter(iter);
-        assert_eq!(
-            Ok((0..10).collect::<Vec<_>>()),
-            try_stream_of_results(stream).await
-        );
-
-        let iter = vec![async move { Result::<i32, String>::Err("test".to_string()) }];
-        let stream = futures::stream::iter(iter);
-        assert_eq!(Err("test".to_string()), try_stream_of_results(stream).await);
-
-        let iter = (0..10).map(|_| async move { Result::<i32, String>::Err("test".to_string()) });
-        let stream = futures::stream::iter(iter);
-        assert_eq!(Err("test".to_string()), try_stream_of_results(stream).await);
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_validation_receipts_resent_receipt_counts_once() -> StateMutationResult<()> {
+        let test_db = crate::test_utils::test_authored_db();
+        let env = test_db.to_db();
+        let keystore = crate::test_utils::test_keystore();
+
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Action),
+        ));
+        let test_op_hash = op.as_hash().clone();
+        env.conn()
+            .unwrap()
+            .with_commit_sync(|txn| mutations::insert_op(txn, &op))
+            .unwrap();
+
+        let vr = ValidationReceipt {
+            dht_op_hash: test_op_hash.clone(),
+            validation_status: ValidationStatus::Valid,
+            validators: vec![
+                keystore.new_sign_keypair_random().await.unwrap(),
+                keystore.new_sign_keypair_random().await.unwrap(),
+            ],
+            when_integrated: Timestamp::now(),
+        };
+        let vr = vr.sign(&keystore).await.unwrap().unwrap();
+        // The same validators send the receipt again, integrated at a different time.
+        let mut resent = vr.receipt.clone();
+        resent.when_integrated = Timestamp::from_micros(0);
+        let resent = resent.sign(&keystore).await.unwrap().unwrap();
+        assert_ne!(vr, resent);
+        // And again, listing the validators in a different order.
+        let mut reordered = vr.receipt.clone();
+        reordered.validators.reverse();
+        let reordered = reordered.sign(&keystore).await.unwrap().unwrap();
+        assert_ne!(vr, reordered);
+
+        env.conn().unwrap().with_commit_sync(|txn| {
+            add_if_unique(txn, vr.clone())?;
+            add_if_unique(txn, resent)?;
+            add_if_unique(txn, reordered)
+        })?;
+
+        env.conn().unwrap().with_reader_test(|reader| {
+            assert_eq!(2, count_valid(&reader, &test_op_hash).unwrap());
+            assert_eq!(vec![vr], list_receipts(&reader, &test_op_hash).unwrap());
+        });
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_validation_receipts_count_each_validator_once() -> StateMutationResult<()> {
+        let test_db = crate::test_utils::test_authored_db();
+        let env = test_db.to_db();
+        let keystore = crate::test_utils::test_keystore();
+
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Action),
+        ));
+        let test_op_hash = op.as_hash().clone();
+        env.conn()
+            .unwrap()
+            .with_commit_sync(|txn| mutations::insert_op(txn, &op))
+            .unwrap();
+
+        let a = keystore.new_sign_keypair_random().await.unwrap();
+        let b = keystore.new_sign_keypair_random().await.unwrap();
+        let receipt = |validators: Vec<AgentPubKey>| {
+            let keystore = keystore.clone();
+            let receipt = ValidationReceipt {
+                dht_op_hash: test_op_hash.clone(),
+                validation_status: ValidationStatus::Valid,
+                validators,
+                when_integrated: Timestamp::now(),
+            };
+            async move { receipt.sign(&keystore).await.unwrap().unwrap() }
+        };
+        let by_a = receipt(vec![a.clone()]).await;
+        let by_a_and_b = receipt(vec![a.clone(), b.clone()]).await;
+        let by_b = receipt(vec![b.clone()]).await;
+        let by_b_and_a = receipt(vec![b, a]).await;
+
+        env.conn().unwrap().with_commit_sync(|txn| {
+            add_if_unique(txn, by_a.clone())?;
+            // B hasn't receipted the op yet, so this is kept.
+            add_if_unique(txn, by_a_and_b.clone())?;
+            // Both have now, so these are dropped.
+            add_if_unique(txn, by_b)?;
+            add_if_unique(txn, by_b_and_a)
+        })?;
+
+        env.conn().unwrap().with_reader_test(|reader| {
+            assert_eq!(2, count_valid(&reader, &test_op_hash).unwrap());
+            let mut list = list_receipts(&reader, &test_op_hash).unwrap();
+            list.sort_by_key(|r| r.receipt.validators.len());
+            assert_eq!(vec![by_a, by_a_and_b], list);
+        });
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_validation_receipts_verify_signatures() {
+        let keystore = crate::test_utils::test_keystore();
+        let op_hash = fixt!(DhtOpHash);
+
+        let vr = fake_vr(&op_hash, &keystore).await;
+        assert!(vr.verify().await);
+
+        // The content no longer matches what was signed.
+        let mut tampered = vr.clone();
+        tampered.receipt.validation_status = ValidationStatus::Rejected;
+        assert!(!tampered.verify().await);
+
+        // Signed by someone other than the claimed validator.
+        let mut forged = vr.clone();
+        forged.validators_signatures = fake_vr(&op_hash, &keystore).await.validators_signatures;
+        assert!(!forged.verify().await);
+
+        let mut unsigned = vr.clone();
+        unsigned.validators_signatures.clear();
+        assert!(!unsigned.verify().await);
+
+        // The same validator listed twice, even with genuine signatures.
+        let mut repeated = vr.receipt.clone();
+        repeated.validators.push(repeated.validators[0].clone());
+        let repeated = repeated.sign(&keystore).await.unwrap().unwrap();
+        assert!(!repeated.verify().await);
+
+        // Signatures that don't line up with their validators.
+        let mut pair = vr.receipt;
+        pair.validators
+            .push(keystore.new_sign_keypair_random().await.unwrap());
+        let pair = pair.sign(&keystore).await.unwrap().unwrap();
+        assert!(pair.verify().await);
+        let mut swapped = pair;
+        swapped.validators_signatures.reverse();
+        assert!(!swapped.verify().await);
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use holo_hash::WasmHash;
@@ -49,6 +50,57 @@ pub fn put(txn: &mut Transaction, wasm: DnaWasmHashed) -> StateMutationResult<()
     mutations::insert_wasm(txn, wasm)
 }
 
+/// The outcome of [`delete_unreferenced`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// The wasms that were (or with `dry_run`, would be) deleted.
+    pub removed: Vec<WasmHash>,
+    /// The total size of the removed wasm blobs.
+    pub bytes_reclaimed: u64,
+}
+
+/// Delete every wasm that is not used by a zome of any [`DnaDef`] in this database.
+/// With `dry_run` the report is built but nothing is deleted.
+///
+/// References are gathered inside the same write transaction that deletes,
+/// so a DNA being installed concurrently can't lose its wasm.
+pub fn delete_unreferenced(txn: &mut Transaction, dry_run: bool) -> StateMutationResult<GcReport> {
+    let dna_defs = crate::dna_def::get_all(txn)?;
+    let referenced: HashSet<WasmHash> = dna_defs
+        .iter()
+        .flat_map(|dna_def| {
+            dna_def
+                .all_zomes()
+                .filter_map(|(zome_name, zome_def)| zome_def.wasm_hash(zome_name).ok())
+        })
+        .collect();
+
+    let mut report = GcReport::default();
+    let mut stmt = txn.prepare("SELECT hash, length(blob) FROM Wasm")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let hash: WasmHash = row.get(0)?;
+        if !referenced.contains(&hash) {
+            report.bytes_reclaimed += row.get::<_, u64>(1)?;
+            report.removed.push(hash);
+        }
+    }
+    drop(rows);
+    drop(stmt);
+
+    if !dry_run {
+        for hash in &report.removed {
+            txn.execute(
+                "DELETE FROM Wasm WHERE hash = :hash",
+                named_params! {
+                    ":hash": hash
+                },
+            )?;
+        }
+    }
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +136,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreferenced_wasm_is_deleted() {
+        use holochain_sqlite::prelude::*;
+
+        let db = crate::test_utils::test_wasm_db();
+
+        let wasms = futures::future::join_all((1..=3u8).map(|i| {
+            DnaWasmHashed::from_content(DnaWasm {
+                code: Arc::new(vec![i; i as usize * 100].into_boxed_slice()),
+            })
+        }))
+        .await;
+        let hashes: Vec<WasmHash> = wasms.iter().map(|w| w.as_hash().clone()).collect();
+
+        // Two DNAs, both using the first wasm and one also using the second.
+        let zome = |name: &str, hash: &WasmHash| {
+            IntegrityZome::new(
+                name.into(),
+                ZomeDef::Wasm(WasmZome::new(hash.clone())).into(),
+            )
+        };
+        let dna_defs = [
+            DnaDef::unique_from_zomes(vec![zome("foo", &hashes[0])], vec![]),
+            DnaDef::unique_from_zomes(
+                vec![zome("foo", &hashes[0]), zome("anchor", &hashes[1])],
+                vec![],
+            ),
+        ];
+
+        db.test_commit(|txn| {
+            for wasm in wasms {
+                put(txn, wasm).unwrap();
+            }
+            for dna_def in dna_defs {
+                crate::dna_def::put(txn, dna_def).unwrap();
+            }
+        });
+
+        let expected = GcReport {
+            removed: vec![hashes[2].clone()],
+            bytes_reclaimed: 300,
+        };
+
+        let report = db.test_commit(|txn| delete_unreferenced(txn, true).unwrap());
+        assert_eq!(report, expected);
+        fresh_reader_test!(db, |txn| {
+            assert!(contains(&txn, &hashes[2]).unwrap());
+        });
+
+        let report = db.test_commit(|txn| delete_unreferenced(txn, false).unwrap());
+        assert_eq!(report, expected);
+        fresh_reader_test!(db, |txn| {
+            assert!(contains(&txn, &hashes[0]).unwrap());
+            assert!(contains(&txn, &hashes[1]).unwrap());
+            assert!(!contains(&txn, &hashes[2]).unwrap());
+        });
+    }
 }
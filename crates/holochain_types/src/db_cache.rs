@@ -205,6 +205,18 @@ impl DhtDbQueryCache {
         }))
     }
 
+    /// The highest integrated action sequence for this [`AgentPubKey`], if any.
+    pub async fn integrated_upper_bound(
+        &self,
+        author: &AgentPubKey,
+    ) -> DatabaseResult<Option<u32>> {
+        Ok(self.get_or_try_init().await?.share_ref(|activity| {
+            activity
+                .get(author)
+                .and_then(|state| state.bounds.integrated)
+        }))
+    }
+
     /// Mark agent activity as actually integrated.
     pub async fn set_all_activity_to_integrated(
         &self,